@@ -0,0 +1,62 @@
+use crate::utils::database::{Alias, FileIndex, IndexSQL};
+use crate::utils::query_factory::FileFilter;
+
+// 别名：用户为应用或文件设置的简短关键词，如 ff -> Firefox、wx -> 微信，
+// 按路径关联，重建应用索引后仍然有效；检索时别名匹配的条目排在最前
+
+// 别名可以关联的索引表
+const TARGETS: [&str; 2] = ["app", "file"];
+
+fn check_target(target: &str) -> Result<(), String> {
+    if TARGETS.contains(&target) {
+        Ok(())
+    } else {
+        Err(format!("不支持的别名类型:{}", target))
+    }
+}
+
+/// 别名匹配的条目放在第一页最前，之后的页去掉这些条目以免重复；keyword 为去掉过滤条件后的关键词
+pub fn prepend_alias_matches(target: &str, keyword: &str, filter: &FileFilter, offset: i32, mut result: Vec<FileIndex>) -> Vec<FileIndex> {
+    let mut aliased = IndexSQL::new().find_by_alias(target, keyword, filter).unwrap_or_else(|e| {
+        println!("读取别名失败:{:?}", e);
+        vec![]
+    });
+    result.retain(|item| !aliased.iter().any(|a| a.path == item.path));
+    if offset > 0 {
+        return result;
+    }
+    aliased.extend(result);
+    aliased
+}
+
+/// 为 app_index 或 file_index 中的条目添加别名，target 为 app 或 file
+#[tauri::command(rename_all = "camelCase")]
+pub fn add_alias(target: &str, path: &str, alias: &str) -> Result<(), String> {
+    check_target(target)?;
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return Err("别名不能为空".to_string());
+    }
+    let db = IndexSQL::new();
+    match db.find_by_path(target, path) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(format!("索引中不存在:{}", path)),
+        Err(e) => return Err(e.to_string()),
+    }
+    db.insert_alias(target, path, alias).map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub fn remove_alias(target: &str, path: &str, alias: &str) -> Result<(), String> {
+    check_target(target)?;
+    IndexSQL::new().delete_alias(target, path, alias.trim()).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// 别名列表，target、path 为空时返回全部
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_aliases(target: Option<String>, path: Option<String>) -> Result<Vec<Alias>, String> {
+    if let Some(target) = &target {
+        check_target(target)?;
+    }
+    IndexSQL::new().find_aliases(target.as_deref(), path.as_deref()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+use std::thread;
+use crate::config;
+use crate::utils::database::{FileContent, FileIndex, IndexSQL};
+use crate::utils::extract_factory::{extract_text, ExtractError, DOCUMENT_EXTS};
+use crate::utils::query_factory::{parse_query, FileQuery};
+
+/// 内容搜索前缀，如 content:todo ext:rs
+pub const CONTENT_SEARCH_PREFIX: &str = "content:";
+
+// 提取内容的文本类文件
+pub const TEXT_EXTS: &[&str] = &[
+    "txt", "text", "md", "markdown", "rst", "org", "csv", "tsv",
+    "json", "jsonc", "yaml", "yml", "toml", "ini", "cfg", "conf", "properties", "env", "xml", "plist",
+    "html", "htm", "css", "scss", "less", "vue", "svelte",
+    "js", "mjs", "cjs", "jsx", "ts", "tsx", "rs", "py", "go", "java", "kt", "kts", "scala", "groovy", "gradle",
+    "c", "h", "cc", "cpp", "hpp", "cs", "m", "mm", "swift", "rb", "php", "pl", "lua", "r", "dart",
+    "sh", "bash", "zsh", "fish", "ps1", "bat", "cmd", "sql", "graphql", "proto", "tex", "srt",
+];
+// 每批提取的文件数
+const BATCH_SIZE: usize = 100;
+
+/// 文件内容索引，提取文本文件、PDF 和 Office 文档的内容写入全文索引，配置关闭时不工作
+#[derive(Clone)]
+pub struct ContentIndexer {
+    max_size: u64,
+    document_max_size: u64,
+}
+
+impl ContentIndexer {
+    pub fn from_config() -> Option<Self> {
+        let config = config::Config::new();
+        if !config.get_file_content_index_switch() {
+            return None;
+        }
+        Some(Self {
+            max_size: config.get_file_content_index_max_size(),
+            document_max_size: config.get_file_content_index_document_max_size(),
+        })
+    }
+
+    /// 启动时在后台补齐未提取或已修改文件的内容
+    pub fn start() {
+        thread::spawn(|| {
+            if let Some(indexer) = Self::from_config() {
+                let mut index_db = IndexSQL::new();
+                let count = indexer.index_pending(&mut index_db, None);
+                println!("文件内容索引完成:{:?}", count);
+            }
+        });
+    }
+
+    /// 提取未索引或已修改文件的内容，path 不为空时只处理该路径及其子路径，返回处理的文件数
+    pub fn index_pending(&self, index_db: &mut IndexSQL, path: Option<&str>) -> usize {
+        let exts: Vec<&str> = TEXT_EXTS.iter().chain(DOCUMENT_EXTS).copied().collect();
+        let mut count = 0;
+        loop {
+            let files = match index_db.find_content_pending(&exts, path, BATCH_SIZE) {
+                Ok(files) => files,
+                Err(e) => {
+                    println!("读取待提取内容的文件失败:{:?}", e);
+                    break;
+                }
+            };
+            if files.is_empty() {
+                break;
+            }
+            count += files.len();
+            let contents = files.into_iter().map(|file| self.extract(file)).collect();
+            if let Err(e) = index_db.save_file_contents(contents) {
+                println!("保存文件内容失败:{:?}", e);
+                break;
+            }
+        }
+        count
+    }
+
+    /// 提取单个文件的内容，只处理文本和文档类型的文件，内容已是最新时跳过
+    pub fn index_file(&self, index_db: &mut IndexSQL, path: &Path) {
+        let ext = extension_of(path);
+        if !TEXT_EXTS.contains(&ext.as_str()) && !DOCUMENT_EXTS.contains(&ext.as_str()) || !path.is_file() {
+            return;
+        }
+        match index_db.find_content_pending_file(&path.display().to_string()) {
+            Ok(Some(file)) => {
+                if let Err(e) = index_db.save_file_contents(vec![self.extract(file)]) {
+                    println!("保存文件内容失败:{:?} {:?}", path, e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("读取待提取内容的文件失败:{:?} {:?}", path, e),
+        }
+    }
+
+    fn extract(&self, file: FileIndex) -> FileContent {
+        let path = Path::new(&file.path);
+        let is_document = DOCUMENT_EXTS.contains(&extension_of(path).as_str());
+        let max_size = if is_document { self.document_max_size } else { self.max_size };
+        let mut content = FileContent { id: file.id, size: file.size, mtime: file.mtime, ..Default::default() };
+        match extract_text(path, max_size) {
+            Ok(mut text) => {
+                // 文档解压后的文本可能远大于文件本身，按文本大小上限截断
+                if text.len() as u64 > self.max_size {
+                    let mut end = self.max_size as usize;
+                    while !text.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    text.truncate(end);
+                }
+                content.content = text;
+                content.status = "ok".to_string();
+            }
+            Err(e) => {
+                content.status = match e {
+                    ExtractError::Failed(_) => "failed".to_string(),
+                    _ => "skipped".to_string(),
+                };
+                content.reason = e.reason();
+            }
+        }
+        content
+    }
+}
+
+// 小写的扩展名，没有扩展名时为空
+fn extension_of(path: &Path) -> String {
+    path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).unwrap_or_default()
+}
+
+// 摘要中的换行等空白压缩为单个空格，并标记关键词出现的字符区间
+fn highlight_snippet(window: &str, keyword: &str) -> (String, Vec<(usize, usize)>) {
+    fn fold(c: char) -> char {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+    fn collapse(text: &str) -> Vec<char> {
+        let mut chars: Vec<char> = vec![];
+        for c in text.chars() {
+            if !c.is_whitespace() {
+                chars.push(c);
+            } else if chars.last().is_some_and(|last| *last != ' ') {
+                chars.push(' ');
+            }
+        }
+        while chars.last() == Some(&' ') {
+            chars.pop();
+        }
+        chars
+    }
+    let chars = collapse(window);
+    let folded: Vec<char> = chars.iter().map(|&c| fold(c)).collect();
+    let pattern: Vec<char> = collapse(keyword).into_iter().map(fold).collect();
+    let mut ranges = vec![];
+    let mut i = 0;
+    while !pattern.is_empty() && i + pattern.len() <= folded.len() {
+        if folded[i..i + pattern.len()] == pattern[..] {
+            ranges.push((i, i + pattern.len()));
+            i += pattern.len();
+        } else {
+            i += 1;
+        }
+    }
+    (chars.into_iter().collect(), ranges)
+}
+
+/// 文件内容搜索，返回命中的文件及高亮摘要，同样支持 ext:、size:、modified: 等过滤条件
+pub fn search_file_content(input: &str, offset: i32) -> Vec<FileIndex> {
+    let FileQuery { keyword, filter } = parse_query(input);
+    if keyword.is_empty() {
+        return vec![];
+    }
+    let db = IndexSQL::new();
+    let mut result = match db.find_by_content(&keyword, &filter, offset) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("内容搜索失败:{:?}", e);
+            return vec![];
+        }
+    };
+    for item in result.iter_mut() {
+        let (snippet, ranges) = highlight_snippet(&item.snippet, &keyword);
+        item.snippet = snippet;
+        item.snippet_highlight = ranges;
+    }
+    result
+}
+
+#[test]
+fn test_highlight_snippet() {
+    let (snippet, ranges) = highlight_snippet("fn main() {\n    // TODO: 完善\n    todo!()\n}\n", "todo");
+    assert_eq!(snippet, "fn main() { // TODO: 完善 todo!() }");
+    assert_eq!(ranges, vec![(15, 19), (24, 28)]);
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tauri::Window;
+use crate::api::verifier::is_missing;
+use crate::utils::database::{DuplicateGroup, FileIndex, IndexSQL};
+use crate::utils::string_factory;
+
+// 重复文件查找：先按大小分组，再比较文件头尾的部分哈希，最后比较完整内容的哈希；
+// 通过 duplicate_scan_progress、duplicate_scan_finished 事件通知前端，结果保存在 duplicate_file 表
+
+// 默认忽略的小文件，字节
+const DEFAULT_MIN_SIZE: u64 = 1024;
+// 部分哈希读取文件头尾各 PARTIAL_SIZE 字节，不超过两倍的文件部分哈希即完整内容
+const PARTIAL_SIZE: u64 = 64 * 1024;
+// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+// 每页返回的重复文件组数
+const PAGE_SIZE: i32 = 10;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+// 查找结束时清除运行标记，查找线程 panic 时同样清除，避免之后无法再次查找
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DuplicateScanStatus {
+    /// 有相同大小文件的候选数
+    pub candidates: usize,
+    /// 已计算哈希的文件数
+    pub hashed: usize,
+    pub groups: usize,
+    /// 全部重复副本占用的空间
+    pub wasted: u64,
+    pub error: Option<String>,
+}
+
+// 文件头尾的哈希，文件大小与索引记录不一致时跳过，可能在扫描后被修改
+fn partial_hash(file: &FileIndex) -> io::Result<String> {
+    let mut f = File::open(&file.path)?;
+    if f.metadata()?.len() != file.size {
+        return Err(io::Error::new(io::ErrorKind::Other, "文件大小已变化"));
+    }
+    let mut head = Vec::with_capacity(PARTIAL_SIZE as usize * 2);
+    (&mut f).take(PARTIAL_SIZE).read_to_end(&mut head)?;
+    if file.size > PARTIAL_SIZE * 2 {
+        f.seek(SeekFrom::End(-(PARTIAL_SIZE as i64)))?;
+        f.take(PARTIAL_SIZE).read_to_end(&mut head)?;
+    } else {
+        f.read_to_end(&mut head)?;
+    }
+    Ok(string_factory::md5_by_bytes(&head))
+}
+
+fn full_hash(file: &FileIndex) -> io::Result<String> {
+    string_factory::md5_by_reader(&mut File::open(&file.path)?)
+}
+
+// 指向同一文件的硬链接不占用额外空间，每个文件只保留一个路径
+#[cfg(unix)]
+fn dedup_hard_links(files: Vec<FileIndex>) -> Vec<FileIndex> {
+    use std::os::unix::fs::MetadataExt;
+    let mut seen = std::collections::HashSet::new();
+    files.into_iter().filter(|file| {
+        fs::metadata(&file.path).is_ok_and(|meta| seen.insert((meta.dev(), meta.ino())))
+    }).collect()
+}
+
+#[cfg(not(unix))]
+fn dedup_hard_links(files: Vec<FileIndex>) -> Vec<FileIndex> {
+    files
+}
+
+// 按哈希分组，只保留有多个文件的组
+fn group_by_hash<F>(files: Vec<FileIndex>, status: &mut DuplicateScanStatus, hash: F) -> Vec<(String, Vec<FileIndex>)>
+    where F: Fn(&FileIndex) -> io::Result<String>
+{
+    let mut groups: HashMap<String, Vec<FileIndex>> = HashMap::new();
+    for file in files {
+        status.hashed += 1;
+        match hash(&file) {
+            Ok(hash) => groups.entry(hash).or_default().push(file),
+            Err(e) => println!("计算文件哈希失败:{:?} {:?}", file.path, e),
+        }
+    }
+    groups.into_iter().filter(|(_, files)| files.len() > 1).collect()
+}
+
+/// 在已按大小降序排列的候选中查找内容相同的文件组
+pub fn find_duplicates(candidates: Vec<FileIndex>, mut on_progress: impl FnMut(&DuplicateScanStatus)) -> (Vec<DuplicateGroup>, DuplicateScanStatus) {
+    let mut status = DuplicateScanStatus { candidates: candidates.len(), ..Default::default() };
+    let mut groups = vec![];
+    let mut candidates = candidates.into_iter().peekable();
+    while let Some(first) = candidates.next() {
+        let size = first.size;
+        let mut same_size = vec![first];
+        while let Some(file) = candidates.next_if(|file| file.size == size) {
+            same_size.push(file);
+        }
+        let same_size = dedup_hard_links(same_size);
+        if same_size.len() < 2 {
+            continue;
+        }
+        for (hash, files) in group_by_hash(same_size, &mut status, partial_hash) {
+            let matched = if size <= PARTIAL_SIZE * 2 {
+                vec![(hash, files)]
+            } else {
+                group_by_hash(files, &mut status, full_hash)
+            };
+            for (hash, files) in matched {
+                let wasted = size * (files.len() as u64 - 1);
+                status.groups += 1;
+                status.wasted += wasted;
+                groups.push(DuplicateGroup { id: 0, hash, size, wasted, files });
+            }
+        }
+        on_progress(&status);
+    }
+    groups.sort_by(|a, b| b.wasted.cmp(&a.wasted));
+    (groups, status)
+}
+
+// 查找并保存结果
+fn scan(window: &Window, min_size: u64) -> Result<DuplicateScanStatus> {
+    let mut index_db = IndexSQL::new();
+    let candidates = index_db.find_duplicate_candidates(min_size)?;
+    println!("重复文件候选:{:?}", candidates.len());
+    let mut last_progress = Instant::now();
+    let (groups, status) = find_duplicates(candidates, |status| {
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let _ = window.emit("duplicate_scan_progress", status.clone());
+        }
+    });
+    index_db.save_duplicate_groups(&groups)?;
+    Ok(status)
+}
+
+/// 在后台查找重复文件，已有查找在运行时返回 false
+#[tauri::command(rename_all = "camelCase")]
+pub fn find_duplicate_files(window: Window, min_size: Option<u64>) -> bool {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    let guard = RunningGuard;
+    thread::spawn(move || {
+        let min_size = min_size.unwrap_or(DEFAULT_MIN_SIZE).max(1);
+        let status = scan(&window, min_size).unwrap_or_else(|e| {
+            DuplicateScanStatus { error: Some(e.to_string()), ..Default::default() }
+        });
+        println!("重复文件查找结束:{:?}", status);
+        drop(guard);
+        let _ = window.emit("duplicate_scan_finished", status);
+    });
+    true
+}
+
+/// 按浪费空间降序读取上次查找的结果，已不存在的副本从结果中移除
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_duplicate_groups(keyword: Option<String>, offset: i32) -> Vec<DuplicateGroup> {
+    let mut index_db = IndexSQL::new();
+    let keyword = keyword.unwrap_or_default();
+    let groups = index_db.find_duplicate_groups(keyword.trim(), PAGE_SIZE, offset).unwrap_or_else(|e| {
+        println!("读取重复文件失败:{:?}", e);
+        vec![]
+    });
+    let missing: Vec<&str> = groups.iter().flat_map(|group| group.files.iter())
+        .filter(|file| is_missing(&file.path)).map(|file| file.path.as_str()).collect();
+    if missing.is_empty() {
+        return groups;
+    }
+    for path in missing {
+        let _ = index_db.delete_duplicate_file(path);
+    }
+    index_db.find_duplicate_groups(keyword.trim(), PAGE_SIZE, offset).unwrap_or_default()
+}
+
+// 以 KB、MB、GB 显示大小
+fn format_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", size) } else { format!("{:.1} {}", value, units[unit]) }
+}
+
+/// 启动器中按组列出重复文件，desc 为路径、副本数和可释放的空间
+pub fn search_duplicate_files(keyword: &str, offset: i32) -> Vec<FileIndex> {
+    get_duplicate_groups(Some(keyword.to_string()), offset).into_iter().flat_map(|group| {
+        let count = group.files.len();
+        let wasted = format_size(group.wasted);
+        group.files.into_iter().map(move |mut file| {
+            file.desc = format!("{} · {} 份相同 · 可释放 {}", file.path, count, wasted);
+            file.file_type = Path::new(&file.path).extension().map_or(String::new(), |ext| ext.to_string_lossy().to_string());
+            file
+        })
+    }).collect()
+}
+
+/// 把重复文件的一个副本移到回收站，并从文件索引和重复文件结果中移除；
+/// 只处理重复文件结果中的路径，且同组至少还有一份其他副本存在
+#[tauri::command(rename_all = "camelCase")]
+pub fn trash_duplicate_file(path: &str) -> Result<(), String> {
+    if !Path::new(path).is_file() {
+        return Err(format!("文件不存在:{}", path));
+    }
+    let mut index_db = IndexSQL::new();
+    let copies = index_db.find_duplicate_copies(path).map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("不在重复文件结果中:{}", path))?;
+    if !copies.iter().any(|copy| copy != path && !is_missing(copy)) {
+        return Err(format!("已是最后一份副本:{}", path));
+    }
+    move_to_trash(path).map_err(|e| e.to_string())?;
+    match index_db.delete_duplicate_file(path) {
+        Ok(Some(file_id)) => {
+            let _ = index_db.delete_by_ids("file", &[file_id]);
+        }
+        Ok(None) => {}
+        Err(e) => println!("移除重复文件记录失败:{:?}", e),
+    }
+    Ok(())
+}
+
+// 移到系统回收站，可以从回收站恢复
+fn move_to_trash(path: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]{
+        use std::process::Command;
+        let path = path.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("tell application \"Finder\" to delete POSIX file \"{}\"", path);
+        let output = Command::new("osascript").args(["-e", &script]).output()?;
+        if !output.status.success() {
+            return Err(anyhow!("移到废纸篓失败:{}", String::from_utf8_lossy(&output.stderr)));
+        }
+    }
+    #[cfg(target_os = "windows")]{
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::shellapi::{SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE, SHFILEOPSTRUCTW};
+        // pFrom 以两个 0 结尾
+        let from: Vec<u16> = OsStr::new(path).encode_wide().chain([0, 0]).collect();
+        let mut operation = SHFILEOPSTRUCTW {
+            hwnd: std::ptr::null_mut(),
+            wFunc: FO_DELETE as u32,
+            pFrom: from.as_ptr(),
+            pTo: std::ptr::null(),
+            fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT) as u16,
+            fAnyOperationsAborted: 0,
+            hNameMappings: std::ptr::null_mut(),
+            lpszProgressTitle: std::ptr::null(),
+        };
+        let code = unsafe { SHFileOperationW(&mut operation) };
+        if code != 0 || operation.fAnyOperationsAborted != 0 {
+            return Err(anyhow!("移到回收站失败:{}", code));
+        }
+    }
+    #[cfg(target_os = "linux")]{
+        use std::process::Command;
+        let output = Command::new("gio").args(["trash", "--", path]).output()?;
+        if !output.status.success() {
+            return Err(anyhow!("移到回收站失败:{}", String::from_utf8_lossy(&output.stderr)));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_find_duplicates() {
+    let dir = std::env::temp_dir().join("lark_test_duplicates");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let large = vec![7u8; PARTIAL_SIZE as usize * 3];
+    let mut changed = large.clone();
+    changed[PARTIAL_SIZE as usize + 1] = 8;
+    let files = [("a.txt", b"hello".to_vec()), ("b.txt", b"hello".to_vec()), ("c.txt", b"world".to_vec()),
+        ("d.bin", large.clone()), ("e.bin", large), ("f.bin", changed)];
+    let mut candidates: Vec<FileIndex> = files.iter().map(|(name, content)| {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        FileIndex { path: path.to_string_lossy().to_string(), size: content.len() as u64, ..Default::default() }
+    }).collect();
+    candidates.sort_by(|a, b| b.size.cmp(&a.size));
+    let (groups, status) = find_duplicates(candidates, |_| {});
+    assert_eq!(groups.len(), 2);
+    // 中间字节不同的大文件在完整哈希时被区分
+    assert_eq!(groups[0].files.len(), 2);
+    assert_eq!(groups[0].wasted, PARTIAL_SIZE * 3);
+    assert!(groups[1].files.iter().all(|file| file.path.ends_with("a.txt") || file.path.ends_with("b.txt")));
+    assert_eq!(status.wasted, PARTIAL_SIZE * 3 + 5);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5 MB");
+}
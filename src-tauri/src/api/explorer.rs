@@ -1,11 +1,12 @@
 use std::any::type_name;
 use std::ascii::escape_default;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Cursor, Read};
 use std::{default, panic};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
 use base64::encode;
 use base64::engine::{general_purpose, Engine};
 use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
@@ -14,28 +15,55 @@ use icns::{IconFamily, IconType};
 use image::DynamicImage;
 use pinyin::{Pinyin, ToPinyin};
 use plist::Value;
-use rayon::iter::{ParallelBridge, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+use std::sync::atomic::{AtomicU64, Ordering};
 use log::{debug, info};
-use tauri::{AppHandle, Manager};
-use walkdir::{WalkDir, DirEntry};
+use tauri::{AppHandle, Manager, Window};
 use crate::config;
-use crate::utils::database::{FileIndex, IndexSQL};
+use crate::config::IndexRoot;
+use crate::api::alias::prepend_alias_matches;
+use crate::api::content::ContentIndexer;
+use crate::api::frecency::{Frecency, APP_PROVIDER};
+use crate::api::index_job::IndexJob;
+use crate::api::verifier::{is_missing, IndexVerifier};
+use crate::utils::database::{FileIndex, IndexSQL, SHADOW_INDEX_PREFIX};
 use crate::utils::string_factory::text_to_pinyin;
+use crate::utils::fuzzy_factory::fuzzy_match;
+use crate::utils::shuangpin::shuangpin_to_pinyin;
+use crate::utils::query_factory::{parse_query, FileFilter, FileQuery};
+use crate::utils::ignore_factory::IgnoreFiles;
+use crate::utils::glob_factory::{PathRule, PathRules};
+use crate::utils::volumes::{self, Volume};
+#[cfg(target_os = "windows")]
 use crate::utils::icons;
+#[cfg(target_os = "linux")]
+use crate::utils::{appimage, desktop_entry};
 
+#[cfg(target_os = "windows")]
 use std::ffi::{OsStr, OsString};
 use std::ops::Index;
+#[cfg(target_os = "windows")]
 use std::os::windows::ffi::{OsStrExt, OsStringExt};
+#[cfg(target_os = "windows")]
 use std::ptr;
+#[cfg(target_os = "windows")]
 use winapi::um::winuser::{GetWindowTextLengthW, GetWindowTextW, EnumWindows, FindWindowW, SetForegroundWindow, ShowWindow, SW_RESTORE};
+#[cfg(target_os = "windows")]
 use winapi::um::processthreadsapi::{STARTUPINFOW, CreateProcessW, PROCESS_INFORMATION};
+#[cfg(target_os = "windows")]
 use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32};
+#[cfg(target_os = "windows")]
 use winapi::shared::ntdef::HANDLE;
+#[cfg(target_os = "windows")]
 use winapi::shared::windef::HWND;
+#[cfg(target_os = "windows")]
 use winapi::shared::minwindef::LPARAM;
+#[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 pub fn to_pinyin(hans: &str) -> Vec<String> {
@@ -101,21 +129,146 @@ pub fn search_files(keyword: &str) -> Vec<HashMap<String, String>> {
     result
 }
 
-pub fn search_file_index(keyword: &str, offset: i32) -> Vec<FileIndex> {
+const SEARCH_PAGE_SIZE: usize = 30;
+// 标题与输入完全一致时的额外加分，保证排在最前
+const EXACT_MATCH_BONUS: i64 = 1000;
+
+// 搜索词的全部形式：原始输入，以及开启双拼时转换得到的全拼。
+// 只有整个输入都是字母且每两键都能解析为音节时才按双拼转换，避免英文输入得到无意义的拼音；
+// 末尾未输完的单键按声母转换，输入过程中也能前缀匹配
+fn keyword_variants(keyword: &str) -> Vec<String> {
+    let mut keywords = vec![keyword.to_string()];
+    let input = keyword.trim();
+    if !input.chars().all(|c| c.is_ascii_alphabetic() || c == ';') {
+        return keywords;
+    }
+    let mode = config::Config::cached().get_pinyin_input_mode();
+    if let Some(pinyin) = shuangpin_to_pinyin(input, mode) {
+        if pinyin != keyword {
+            keywords.push(pinyin);
+        }
+    }
+    keywords
+}
+
+// 模糊匹配评分，标题命中时记录高亮区间；拼音（含多音字的各种读音）、首字母、应用关键词以及应用路径中的英文名也参与评分
+fn fuzzy_score(keywords: &[String], item: &mut FileIndex, match_path: bool) -> bool {
+    let mut best: Option<i64> = None;
+    item.highlight.clear();
+    let stem = Path::new(&item.path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let mut alternatives: Vec<&str> = item.pinyin.split_whitespace().chain(item.abb.split_whitespace())
+        .chain(item.keywords.split_whitespace()).collect();
+    if match_path {
+        alternatives.push(&stem);
+    }
+    for keyword in keywords {
+        if let Some(m) = fuzzy_match(keyword, &item.title) {
+            let exact = item.title.to_lowercase() == keyword.trim().to_lowercase();
+            let score = m.score + if exact { EXACT_MATCH_BONUS } else { 0 };
+            if best < Some(score) {
+                best = Some(score);
+                item.highlight = m.ranges;
+            }
+        }
+        for alternative in &alternatives {
+            if let Some(m) = fuzzy_match(keyword, alternative) {
+                if best < Some(m.score) {
+                    best = Some(m.score);
+                    item.highlight.clear();
+                }
+            }
+        }
+    }
+    item.score = best.unwrap_or(0);
+    best.is_some()
+}
+
+/// 文件搜索，输入中的 ext:、size:、modified:、created:、kind: 作为过滤条件
+pub fn search_file_index(input: &str, offset: i32) -> Vec<FileIndex> {
+    let FileQuery { keyword, filter } = parse_query(input);
+    let keywords = keyword_variants(&keyword);
+    let roots = config::Config::cached().get_index_roots();
+    let weights: Vec<(String, i64)> = roots.iter().map(|root| (root.path.clone(), root.weight)).collect();
     let db = IndexSQL::new();
-    if let Ok(result) = db.find_by_keyword("file", keyword, offset) {
-        return result;
+    let mut result = vec![];
+    let mut ids: HashSet<u64> = HashSet::new();
+    for kw in &keywords {
+        match db.find_by_keyword("file", kw, &filter, &weights, offset) {
+            Ok(items) => result.extend(items.into_iter().filter(|item| ids.insert(item.id))),
+            Err(_) => return vec![FileIndex { ..Default::default() }],
+        }
+    }
+    for item in result.iter_mut() {
+        fuzzy_score(&keywords, item, false);
+    }
+    // 子串匹配不足一页时，用模糊匹配结果补充
+    if offset == 0 && !keyword.is_empty() && result.len() < SEARCH_PAGE_SIZE {
+        let mut candidates = vec![];
+        for kw in &keywords {
+            if let Ok(items) = db.find_fuzzy_candidates("file", kw, &filter, 500) {
+                candidates.extend(items.into_iter().filter(|item| ids.insert(item.id)));
+            }
+        }
+        candidates.retain_mut(|item| fuzzy_score(&keywords, item, false));
+        // 所在根目录的权重加在模糊匹配得分上
+        for item in candidates.iter_mut() {
+            item.score += root_weight(&roots, &item.path);
+        }
+        candidates.sort_by(|a, b| b.score.cmp(&a.score));
+        let remain = SEARCH_PAGE_SIZE - result.len();
+        result.extend(candidates.into_iter().take(remain));
     }
-    return vec![FileIndex { ..Default::default() }];
+    // 已删除但后台校验尚未清理的文件不返回，索引由后台校验清理
+    result.retain(|item| !is_missing(&item.path));
+    result
+}
+
+// 路径所在根目录的排序权重，嵌套时取最近的根目录
+fn root_weight(roots: &[IndexRoot], path: &str) -> i64 {
+    roots.iter()
+        .filter(|root| Path::new(path).starts_with(&root.path))
+        .max_by_key(|root| root.path.len())
+        .map_or(0, |root| root.weight)
 }
+
 pub fn search_app_index(keyword: &str, offset: i32) -> Vec<FileIndex> {
+    let keywords = keyword_variants(keyword);
     let db = IndexSQL::new();
-    if let Ok(result) = db.find_app(keyword, offset) {
-        return result;
+    let mut result = vec![];
+    let mut ids: HashSet<u64> = HashSet::new();
+    for kw in &keywords {
+        match db.find_fuzzy_candidates("app", kw, &FileFilter::default(), 1000) {
+            Ok(items) => result.extend(items.into_iter().filter(|item| ids.insert(item.id))),
+            Err(_) => return vec![FileIndex { ..Default::default() }],
+        }
+    }
+    result.retain_mut(|item| fuzzy_score(&keywords, item, true));
+    result.sort_by(|a, b| b.score.cmp(&a.score));
+    // 启动记录得分加在匹配得分上排序，当前查询词下常用的应用即使没有匹配也会列出，按最佳匹配计分
+    let frecency = Frecency::load(APP_PROVIDER, keyword);
+    let top_score = result.first().map_or(0, |item| item.score);
+    for path in frecency.learned() {
+        if !result.iter().any(|item| item.path == path) {
+            if let Ok(Some(mut item)) = db.find_by_path("app", &path) {
+                item.score = top_score;
+                result.push(item);
+            }
+        }
     }
-    return vec![FileIndex { ..Default::default() }];
+    frecency.rank(&mut result);
+    // 别名匹配的应用排在模糊匹配之前，合并后再分页
+    let result = prepend_alias_matches("app", keyword, &FileFilter::default(), 0, result);
+    let mut result: Vec<FileIndex> = result.into_iter().skip(offset.max(0) as usize).take(SEARCH_PAGE_SIZE).collect();
+    let ids: Vec<u64> = result.iter().map(|item| item.id).collect();
+    if let Ok(icons) = db.find_icons("app", &ids) {
+        for item in result.iter_mut() {
+            item.icon = icons.get(&item.id).cloned().unwrap_or_default();
+        }
+    }
+    result
 }
 
+#[cfg(target_os = "windows")]
 #[derive(Debug)]
 enum ApplicationError {
     UnsupportedExtension,
@@ -124,6 +277,7 @@ enum ApplicationError {
 }
 
 // 从 lnk 文件总获取应用配置
+#[cfg(target_os = "windows")]
 fn get_app_from_lnk(lnk_path: &str) -> Result<Vec<String>, ApplicationError> {
     // println!("快捷方式路径：{}",lnk_path);
 
@@ -219,6 +373,7 @@ fn get_app_from_lnk(lnk_path: &str) -> Result<Vec<String>, ApplicationError> {
 }
 
 
+#[cfg(not(target_os = "linux"))]
 pub fn get_apps(path: &str) -> Vec<HashMap<String, String>> {
     println!("开始检索目录： {:?}", path.replace("\\", "/"));
     let mut applications = Vec::new();
@@ -254,6 +409,7 @@ pub fn get_apps(path: &str) -> Vec<HashMap<String, String>> {
         };
         let mut app_title: String = String::new();
         let mut app_path: String = entry.path().to_str().unwrap().to_string();
+        let mut app_icon: String = app_path.clone();
         #[cfg(target_os = "macos")]{
             if !app_name.ends_with(".app") {
                 // println!("文件夹：{:?}", entry.path().to_str().unwrap());
@@ -347,7 +503,7 @@ pub fn get_apps(path: &str) -> Vec<HashMap<String, String>> {
         }
         println!("应用：{}，路径：{}", app_title, entry.path().to_str().unwrap());
         let map: HashMap<String, String> = HashMap::from([
-            ("icon".to_string(), app_path.clone(),),
+            ("icon".to_string(), app_icon.clone(),),
             ("title".to_string(), app_title.clone()),
             ("desc".to_string(), app_path.clone(),),
             ("data".to_string(), app_path.clone(),),
@@ -358,6 +514,70 @@ pub fn get_apps(path: &str) -> Vec<HashMap<String, String>> {
     applications
 }
 
+// Linux 应用：按 XDG 规范查找的桌面项（含 Flatpak、Snap 导出的桌面项）和配置目录中的 AppImage，
+// 通用名称和关键词随应用一起索引，type 为打包方式
+#[cfg(target_os = "linux")]
+fn desktop_apps(appimage_paths: &[String]) -> Vec<HashMap<String, String>> {
+    desktop_entry::discover().into_iter().chain(appimage::discover(appimage_paths)).map(|entry| {
+        println!("应用：{}，路径：{:?}", entry.name, entry.path);
+        let path = entry.path.to_string_lossy().to_string();
+        let keywords: Vec<String> = std::iter::once(entry.generic_name).chain(entry.keywords)
+            .filter(|keyword| !keyword.is_empty())
+            .collect();
+        HashMap::from([
+            ("icon".to_string(), entry.icon),
+            ("title".to_string(), entry.name),
+            ("desc".to_string(), path.clone()),
+            ("data".to_string(), path),
+            ("type".to_string(), entry.package.as_str().to_string()),
+            ("keywords".to_string(), keywords.join(";")),
+        ])
+    }).collect()
+}
+
+// 关键词及其拼音单独索引，如输入“浏览器”或 browser 可以找到 Firefox；拼音列只保存标题的读音
+#[cfg(target_os = "linux")]
+fn keyword_words(keywords: &str) -> String {
+    let mut words: Vec<String> = vec![];
+    for keyword in keywords.split(';').filter(|keyword| !keyword.is_empty()) {
+        let (keyword_pinyin, _) = text_to_pinyin(keyword);
+        let variants = keyword.split_whitespace().map(str::to_lowercase)
+            .chain(keyword_pinyin.split_whitespace().map(str::to_lowercase));
+        for word in variants {
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+    }
+    words.join(" ")
+}
+
+// 按图标主题目录查找应用图标，返回图标文件路径
+#[cfg(target_os = "linux")]
+fn find_linux_icon(icon: &str) -> Option<PathBuf> {
+    if icon.is_empty() {
+        return None;
+    }
+    let icon_path = Path::new(icon);
+    if icon_path.is_absolute() {
+        return if icon_path.exists() { Some(icon_path.to_path_buf()) } else { None };
+    }
+    let icon_dirs: Vec<PathBuf> = desktop_entry::icon_dirs().into_iter().map(|dir| dir.join("hicolor")).collect();
+    let sizes = ["128x128", "256x256", "96x96", "64x64", "48x48"];
+    for dir in &icon_dirs {
+        for size in &sizes {
+            let path = dir.join(size).join("apps").join(format!("{}.png", icon));
+            if path.exists() {
+                return Some(path);
+            }
+        }
+    }
+    let path = PathBuf::from("/usr/share/pixmaps").join(format!("{}.png", icon));
+    if path.exists() {
+        return Some(path);
+    }
+    None
+}
 
 #[tauri::command(rename_all = "camelCase")]
 pub fn read_file_to_base64(path: &str) -> String {
@@ -417,6 +637,7 @@ pub fn read_icns_to_base64(path: &str) -> Result<String, String> {
 }
 
 // 获取文件图标
+#[cfg(target_os = "windows")]
 #[tauri::command]
 fn read_icon_to_base64(path: String) -> String {
     if let Ok(buffer) = icons::get_icon(&path, 128) {
@@ -504,6 +725,7 @@ pub fn open_explorer(path: &str) -> String {
 fn get_drives() -> Vec<(String, String)> {
     let mut drives = Vec::new();
     use winapi::um::fileapi::{GetDriveTypeW, GetLogicalDrives};
+    use winapi::um::winbase::{DRIVE_CDROM, DRIVE_FIXED, DRIVE_RAMDISK, DRIVE_REMOTE, DRIVE_REMOVABLE};
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStrExt;
     let drive_bits = unsafe { GetLogicalDrives() };
@@ -534,190 +756,662 @@ fn get_drives() -> Vec<(String, String)> {
     drives
 }
 
-fn file_scanning(app_handle: AppHandle, root_dir: &str, skip_dirs: Vec<String>, skip_extensions: Vec<String>) {
-    fn is_hidden(entry: &DirEntry) -> bool {
-        entry.file_name().to_str().map_or(false, |s| s.starts_with('.'))
+// 索引根目录的过滤规则和遍历设置
+#[derive(Clone)]
+struct RootRules {
+    path: PathBuf,
+    rules: PathRules,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+}
+
+/// 文件索引过滤规则，全量扫描与文件监听共用
+#[derive(Clone)]
+pub struct PathFilter {
+    rules: PathRules,
+    // 设置了单独规则的根目录，路径越长越优先
+    roots: Vec<RootRules>,
+    pub ignore_files: IgnoreFiles,
+}
+
+/// 路径检测结果，说明路径是否会被索引以及命中的规则
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PathTestResult {
+    pub path: String,
+    pub indexed: bool,
+    /// 命中的排除或包含规则
+    pub rule: Option<PathRule>,
+    /// 命中的忽略文件及其中的规则
+    pub ignore_file: Option<(String, String)>,
+}
+
+impl PathFilter {
+    pub fn new(rules: PathRules, ignore_files: IgnoreFiles) -> Self {
+        Self { rules, roots: vec![], ignore_files }
+    }
+
+    pub fn from_config() -> Self {
+        let config = config::Config::new();
+        let exclude_paths = config.get_file_search_exclude_paths();
+        let exclude_types = config.get_file_search_exclude_types();
+        let include_paths = config.get_file_search_include_paths();
+        let ignore_files = IgnoreFiles::new(config.get_file_search_ignore_files_switch(), config.get_file_search_ignore_files_roots());
+        let mut filter = Self::new(PathRules::new(&exclude_paths, &exclude_types, &include_paths), ignore_files);
+        let volume_roots = volumes::mounted().into_iter().map(|volume| IndexRoot { path: volume.path, ..Default::default() });
+        for root in file_index_roots().into_iter().chain(volume_roots) {
+            // 根目录本身位于排除路径下时（如 macOS 排除的 /Volumes），该排除规则不作用于这个根目录
+            let root_path = Path::new(&root.path);
+            let inherited: Vec<String> = exclude_paths.iter()
+                .filter(|pattern| !root_path.starts_with(pattern.trim_end_matches('/')))
+                .cloned()
+                .collect();
+            // 根目录的排除规则追加在全局规则之后，优先级更高
+            let rules = if inherited.len() == exclude_paths.len() && root.exclude_paths.is_empty() && root.exclude_types.is_empty() {
+                filter.rules.clone()
+            } else {
+                let paths: Vec<String> = inherited.iter().chain(&root.exclude_paths).cloned().collect();
+                let types: Vec<String> = exclude_types.iter().chain(&root.exclude_types).cloned().collect();
+                PathRules::new(&paths, &types, &include_paths)
+            };
+            filter.roots.push(RootRules {
+                path: PathBuf::from(&root.path),
+                rules,
+                max_depth: root.max_depth,
+                follow_symlinks: root.follow_symlinks,
+            });
+        }
+        filter.roots.sort_by_key(|root| std::cmp::Reverse(root.path.components().count()));
+        filter
     }
 
-    fn should_skip_file(entry: &DirEntry, skip_extensions: &[String]) -> bool {
-        entry.path().extension().and_then(|ext| ext.to_str()).map_or(false, |ext| skip_extensions.contains(&ext.to_lowercase()))
+    fn root_of(&self, path: &Path) -> Option<&RootRules> {
+        self.roots.iter().find(|root| path.starts_with(&root.path))
     }
 
-    fn should_skip_dir(entry: &DirEntry, skip_dirs: &[String]) -> bool {
-        let mut is_skip = false;
-        is_skip = skip_dirs.iter().any(|dir| entry.path().starts_with(dir));
-        if entry.file_name().to_str().unwrap().starts_with("$") {
-            return true;
+    fn rules_for(&self, path: &Path) -> &PathRules {
+        self.root_of(path).map_or(&self.rules, |root| &root.rules)
+    }
+
+    // 相对所在根目录的深度
+    fn depth(&self, path: &Path) -> Option<(usize, Option<usize>)> {
+        let root = self.root_of(path)?;
+        let depth = path.strip_prefix(&root.path).ok()?.components().count();
+        Some((depth, root.max_depth))
+    }
+
+    /// 单个条目是否跳过，目录命中时遍历会跳过整个子树
+    pub fn is_skip(&self, path: &Path, is_dir: bool) -> bool {
+        self.rules_for(path).is_excluded(path, is_dir)
+    }
+
+    /// 路径命中规则、忽略文件或超出根目录的最大深度，用于监听到的单个路径
+    pub fn is_skip_path(&self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        if let Some((depth, Some(max_depth))) = self.depth(path) {
+            if depth > max_depth {
+                return true;
+            }
         }
-        if !is_skip {
-            is_skip = skip_dirs.iter().any(|dir| {
-                if dir.starts_with("*/") {
-                    let skip_key = dir.replace("*/", "");
-                    entry.file_name().to_str().unwrap().contains(skip_key.as_str())
-                } else {
-                    false
-                }
-            });
+        self.is_skip(path, is_dir) || self.ignore_files.is_ignored(path, is_dir)
+    }
+
+    /// 检测路径是否会被索引，返回命中的规则
+    pub fn test_path(&self, path: &Path) -> PathTestResult {
+        let is_dir = path.is_dir();
+        let rule = self.rules_for(path).matched(path, is_dir).cloned();
+        let excluded = rule.as_ref().is_some_and(|rule| !rule.include);
+        // 被规则排除时不再读取忽略文件
+        let ignore_file = if excluded {
+            None
+        } else {
+            self.ignore_files.ignored_by(path, is_dir).map(|(file, glob)| (file.display().to_string(), glob))
+        };
+        PathTestResult {
+            path: path.display().to_string(),
+            indexed: !excluded && ignore_file.is_none(),
+            rule,
+            ignore_file,
         }
-        is_skip
     }
 
-    println!("开始扫描文件夹:{:?}", root_dir);
-    let skip_extensions_data = Arc::new(skip_extensions);
-    let skip_dirs_data = Arc::new(skip_dirs);
-    let files = Arc::new(Mutex::new(Vec::new()));
-    let root_dir = root_dir.to_string();
-    tauri::async_runtime::spawn(async move {
-        let main_window = app_handle.get_window("skylark").unwrap();
-        let mut index_db = IndexSQL::new();
-        WalkDir::new(root_dir).into_iter()
+    /// 遍历目录，跳过命中规则的条目以及 .gitignore、.ignore、.larkignore 忽略的路径；
+    /// 深度和是否跟随符号链接按所在根目录的设置
+    pub fn walk(&self, root: &Path) -> impl Iterator<Item = ignore::DirEntry> {
+        self.walk_entries(root, Arc::default()).filter_map(Result::ok)
+    }
+
+    /// 同 walk，保留遍历错误，并把被排除规则跳过的条目数累加到 skipped
+    pub fn walk_entries(&self, root: &Path, skipped: Arc<AtomicU64>) -> impl Iterator<Item = Result<ignore::DirEntry, ignore::Error>> {
+        fn is_dir(entry: &ignore::DirEntry) -> bool {
+            entry.file_type().is_some_and(|t| t.is_dir())
+        }
+        let rules = self.rules_for(root).clone();
+        let entry_rules = rules.clone();
+        let mut builder = self.ignore_files.walk_builder(root);
+        if let Some(root_rules) = self.root_of(root) {
+            builder.follow_links(root_rules.follow_symlinks);
+        }
+        if let Some((depth, Some(max_depth))) = self.depth(root) {
+            builder.max_depth(Some(max_depth.saturating_sub(depth)));
+        }
+        builder
+            // 被排除的目录下有包含规则时仍需进入
             .filter_entry(move |entry| {
-                !is_hidden(entry) && !should_skip_file(entry, &skip_extensions_data) && !should_skip_dir(entry, &skip_dirs_data)
-            })
-            .filter_map(Result::ok)
-            .for_each(|entry| {
-                let title = entry.file_name().to_str().unwrap_or("").to_string();
-                let file_path = entry.path().display().to_string();
-                let file_type = if entry.path().is_dir() {
-                    "folder".to_string()
-                } else {
-                    entry.path().extension().and_then(|ext| ext.to_str()).unwrap_or("").to_string()
-                };
-                let mut files = files.lock().unwrap();
-                let (pinyin, abb) = text_to_pinyin(&title);
-                files.push(FileIndex {
-                    title,
-                    path: file_path,
-                    pinyin,
-                    abb,
-                    file_type: file_type.to_string(),
-                    ..Default::default()
-                });
-                if files.len() >= 200 {
-                    let _ = index_db.insert_file_indexes(files.clone());
-                    files.clear();
-                    main_window.emit("file_index_count", 200).unwrap();
+                let keep = !entry_rules.is_excluded(entry.path(), is_dir(entry)) || (is_dir(entry) && entry_rules.has_include_under(entry.path()));
+                if !keep {
+                    skipped.fetch_add(1, Ordering::Relaxed);
                 }
-            });
-        let files = files.lock().unwrap().clone();
-        index_db.insert_file_indexes(files).unwrap();
+                keep
+            })
+            .build()
+            .filter(move |entry| entry.as_ref().map_or(true, |entry| !rules.is_excluded(entry.path(), is_dir(entry))))
+    }
+}
+
+/// 检测路径是否会被文件索引收录，并说明命中的排除规则
+#[tauri::command(rename_all = "camelCase")]
+pub fn test_index_path(path: &str) -> PathTestResult {
+    PathFilter::from_config().test_path(Path::new(path))
+}
+
+// 系统时间转秒级时间戳
+fn unix_seconds(time: std::io::Result<SystemTime>) -> Option<i64> {
+    time.ok()?.duration_since(UNIX_EPOCH).ok().map(|duration| duration.as_secs() as i64)
+}
+
+// 文件大小、修改时间和创建时间，文件系统不支持创建时间时取状态变更时间
+fn file_metadata(metadata: &fs::Metadata) -> (u64, i64, i64) {
+    let size = if metadata.is_dir() { 0 } else { metadata.len() };
+    let mtime = unix_seconds(metadata.modified()).unwrap_or(0);
+    let ctime = unix_seconds(metadata.created()).unwrap_or_else(|| {
+        #[cfg(unix)]{
+            use std::os::unix::fs::MetadataExt;
+            metadata.ctime()
+        }
+        #[cfg(not(unix))]{
+            mtime
+        }
     });
+    (size, mtime, ctime)
 }
 
-pub fn create_app_index_to_sql(app_handle: AppHandle) {
-    let config = config::Config::read_local_config().unwrap().base;
-    let mut index_db = IndexSQL::new();
-    let _ = index_db.clear_data("app");
-    let mut result = Vec::new();
+/// 根据路径生成文件索引条目
+pub fn build_file_index(path: &Path) -> FileIndex {
+    let title = path.file_name().and_then(|name| name.to_str()).unwrap_or("").to_string();
+    let metadata = path.metadata().ok();
+    let is_dir = metadata.as_ref().is_some_and(|metadata| metadata.is_dir());
+    let (size, mtime, ctime) = metadata.as_ref().map(file_metadata).unwrap_or_default();
+    let file_type = if is_dir {
+        "folder".to_string()
+    } else {
+        path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_string()
+    };
+    let (pinyin, abb) = text_to_pinyin(&title);
+    FileIndex {
+        title,
+        path: path.display().to_string(),
+        pinyin,
+        abb,
+        file_type,
+        size,
+        mtime,
+        ctime,
+        is_dir,
+        ..Default::default()
+    }
+}
+
+/// 文件索引的根目录，文件监听基于这些目录递归监听；配置了 index_roots 时使用配置
+pub fn file_index_roots() -> Vec<IndexRoot> {
+    let configured = config::Config::new().get_index_roots();
+    if !configured.is_empty() {
+        return configured;
+    }
+    let mut roots = Vec::new();
     #[cfg(target_os = "macos")]{
-        result.extend(get_apps("/System/Applications"));
-        result.extend(get_apps("/Applications/"));
-        result.extend(get_apps(
-            "/System/Volumes/Preboot/Cryptexes/App/System/Applications"
-        ));
+        let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
+        roots.push(home_dir);
     }
     #[cfg(target_os = "windows")]{
-        //  todo 添加到库
+        for driver in get_drives() {
+            if driver.1 == "Fixed Drive" {
+                roots.push(driver.0);
+            }
+        }
+    }
+    #[cfg(target_os = "linux")]{
+        // 主目录以及挂载的真实磁盘，根分区只通过主目录索引
         let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
-        // result.extend(get_apps(r"C:\Program Files\"));
-        // result.extend(get_apps(r"C:\Program Files (x86)\"));
-        result.extend(get_apps(r"C:\ProgramData\Microsoft\Windows\Start Menu\Programs\"));
-        result.extend(get_apps(&format!(r"{}\AppData\Roaming\Microsoft\Windows\Start Menu\Programs\", home_dir.as_str())));
-        result.extend(get_apps(&format!(r"{}\Desktop\", home_dir)));
-        // result.extend();
-        // result.extend(&std::env::var_os("USERPROFILE").unwrap().join("Desktop"));
-        // result.extend(&std::env::var_os("ProgramData").unwrap().join(r"Microsoft\Windows\Start Menu\Programs"));
-    }
-    let items = result.into_iter().map(|app| {
-        let title = app.get("title").unwrap().to_string();
-        let (pinyin, abb) = text_to_pinyin(&title);
-        let mut icon_base64 = String::new();
-        #[cfg(target_os = "macos")]{
-            let local_icon_file = vec!["日历", "迁移助理", "Photo Booth", "系统信息", "系统设置"];
-            let mut icon_file_path = String::new();
-            if !local_icon_file.contains(&title.as_str()) {
-                icon_file_path = read_app_info(&app.get("data").unwrap());
-            } else {
-                icon_file_path = format!("icons/{}.png", title);
+        roots.push(home_dir.clone());
+        // 可移动卷单独建立卷索引
+        let removable: Vec<String> = volumes::removable_volumes().into_iter().map(|volume| volume.path).collect();
+        for mount in crate::utils::mounts::mounted_filesystems() {
+            let mount_path = Path::new(&mount.path);
+            if mount.path == "/" || mount_path.starts_with("/boot") || removable.contains(&mount.path)
+                || mount_path.starts_with(&home_dir) || Path::new(&home_dir).starts_with(mount_path) {
+                continue;
             }
-            icon_base64 = match read_icns_to_base64(&icon_file_path) {
-                Ok(base64) => { base64 }
-                Err(e) => {
-                    println!("错误 {}", e);
-                    "".to_string()
-                }
+            roots.push(mount.path);
+        }
+    }
+    roots.into_iter().map(|path| IndexRoot { path, ..Default::default() }).collect()
+}
+
+// 遍历线程每批发送的条目数
+const SCAN_BATCH_SIZE: usize = 200;
+// 遍历线程与写入线程之间最多排队的批次数，队列满时遍历线程等待，排队的条目不超过 SCAN_QUEUE_SIZE * SCAN_BATCH_SIZE
+const SCAN_QUEUE_SIZE: usize = 16;
+// 写入线程合并到一个事务中的最大条目数
+const WRITE_BATCH_SIZE: usize = 2000;
+// 遍历线程数上限，过多的线程只会加重磁盘随机读
+const MAX_SCAN_THREADS: usize = 4;
+
+// 攒够一批后交给写入线程，队列满时阻塞等待
+fn send_file(tx: &SyncSender<Vec<FileIndex>>, files: &mut Vec<FileIndex>, file: FileIndex) {
+    files.push(file);
+    if files.len() >= SCAN_BATCH_SIZE {
+        let _ = tx.send(std::mem::take(files));
+    }
+}
+
+// 根目录按一级子目录拆分供并行遍历，根目录本身和一级文件直接发送，返回需要遍历的子目录
+fn split_root(filter: &PathFilter, root: &IndexRoot, job: &IndexJob, tx: &SyncSender<Vec<FileIndex>>) -> Vec<PathBuf> {
+    let root_path = Path::new(&root.path);
+    let entries = match fs::read_dir(root_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            job.add_error(format!("读取索引根目录失败:{} {}", root.path, e));
+            return vec![];
+        }
+    };
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    if !filter.is_skip(root_path, true) {
+        job.add_scanned(1);
+        send_file(tx, &mut files, build_file_index(root_path));
+    }
+    for entry in entries {
+        if job.is_cancelled() {
+            return vec![];
+        }
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                job.add_error(e.to_string());
+                continue;
             }
+        };
+        let path = entry.path();
+        let file_type = entry.file_type().ok();
+        // 不跟随符号链接时，指向目录的链接只作为单个条目索引
+        let is_dir = match file_type {
+            Some(t) if t.is_symlink() => root.follow_symlinks && path.is_dir(),
+            Some(t) => t.is_dir(),
+            None => false,
+        };
+        if filter.is_skip_path(&path) && !(is_dir && filter.rules_for(&path).has_include_under(&path)) {
+            job.skipped.fetch_add(1, Ordering::Relaxed);
+            continue;
         }
-        #[cfg(target_os = "windows")]{
-            //todo 获取应用图标
-            icon_base64 = read_icon_to_base64(app.get("desc").unwrap().to_string());
-            println!("{}===>{}", app.get("data").unwrap(), title);
+        if is_dir {
+            dirs.push(path);
+        } else {
+            job.add_scanned(1);
+            send_file(tx, &mut files, build_file_index(&path));
         }
-        FileIndex {
-            title: title.clone(),
-            path: app.get("data").unwrap().to_string(),
-            desc: app.get("desc").unwrap().to_string(),
-            icon: icon_base64,
-            pinyin,
-            abb,
-            ..Default::default()
+    }
+    if !files.is_empty() {
+        let _ = tx.send(files);
+    }
+    dirs
+}
+
+// 遍历一个子目录（包括目录本身），按批发送给写入线程
+fn walk_dir(filter: &PathFilter, dir: &Path, job: &IndexJob, tx: &SyncSender<Vec<FileIndex>>) {
+    let mut files = Vec::with_capacity(SCAN_BATCH_SIZE);
+    for entry in filter.walk_entries(dir, job.skipped.clone()) {
+        if job.is_cancelled() {
+            return;
+        }
+        match entry {
+            Ok(entry) => {
+                job.add_scanned(1);
+                send_file(tx, &mut files, build_file_index(entry.path()));
+            }
+            Err(e) => job.add_error(e.to_string()),
         }
-    }).collect();
-    index_db.insert_app_indexes(items).unwrap();
+    }
+    if !files.is_empty() {
+        let _ = tx.send(files);
+    }
 }
 
-pub fn create_file_index_to_sql(app_handle: AppHandle) {
-    let config = config::Config::read_local_config().unwrap().base;
-    #[cfg(target_os = "macos")]{
-        let index_db = IndexSQL::new();
-        let main_window = app_handle.get_window("skylark").unwrap();
-        let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
-        let child_dir = std::fs::read_dir(&home_dir).unwrap();
-        // 查找用户主目录下所有文件夹
-        for entry in child_dir {
-            if let Ok(dir) = entry {
-                let dir_path = dir.path().to_str().unwrap().to_string();
-                // 过滤隐藏文件
-                if !dir.file_name().to_str().expect(".").starts_with(".") {
-                    // 递归查找文件夹
-                    if dir.path().is_dir() {
-                        let skip_paths = config.local_file_search_exclude_paths.clone();
-                        let skip_extensions = config.local_file_search_exclude_types.clone();
-                        file_scanning(app_handle.clone(), &dir_path, skip_paths, skip_extensions);
-                    } else {
-                        // 文件即入库
-                        let title = dir.file_name().to_str().unwrap_or("").to_string();
-                        let file_type = dir.path().extension().unwrap_or("".as_ref()).to_str().unwrap().to_string();
-                        let f = FileIndex {
-                            title: title.clone(),
-                            file_type,
-                            path: dir_path,
-                            ..Default::default()
-                        };
-                        main_window.emit("file_index_count", &title).unwrap();
-                        index_db.insert_if_not_exist("file", &f).unwrap()
+/// 扫描结果写入的位置
+#[derive(Clone)]
+enum ScanTarget {
+    /// 直接写入主索引
+    Index,
+    /// 写入影子表，全部完成后换入主索引
+    Rebuild,
+    /// 写入卷索引
+    Volume(Volume),
+}
+
+// 打开卷索引：卷的条目只保存在卷索引中，删除主索引中该路径下的旧条目；挂载路径变化时先改写已有条目的路径，保留 id
+fn open_volume_index(volume: &Volume) -> anyhow::Result<(i64, IndexSQL)> {
+    let main_db = IndexSQL::new();
+    let (volume_id, last_path) = main_db.register_volume(volume)?;
+    main_db.delete_by_path("file", &volume.path)?;
+    let mut index_db = IndexSQL::open_volume(volume_id, &volume.uuid);
+    let trim = |path: &str| path.trim_end_matches(['/', '\\']).to_string();
+    if !last_path.is_empty() && trim(&last_path) != trim(&volume.path) {
+        let mut to = build_file_index(Path::new(&volume.path));
+        to.path = trim(&volume.path);
+        let count = index_db.rename_path("file", &trim(&last_path), &to)?;
+        println!("卷挂载路径变化:{:?} -> {:?} {:?}", last_path, volume.path, count);
+    }
+    Ok((volume_id, index_db))
+}
+
+// 唯一的写入线程：合并队列中已有的批次，在一个事务中写入，结束后返回连接供内容索引继续使用；
+// 重建索引时写入影子表
+fn write_file_indexes(mut index_db: IndexSQL, rx: Receiver<Vec<FileIndex>>, window: Window, job: Arc<IndexJob>, shadow: Option<&str>) -> IndexSQL {
+    if let Some(shadow) = shadow {
+        if let Err(e) = index_db.create_shadow_index(shadow) {
+            job.fail(format!("创建重建索引表失败:{:?}", e));
+        }
+    }
+    while let Ok(mut files) = rx.recv() {
+        while files.len() < WRITE_BATCH_SIZE {
+            match rx.try_recv() {
+                Ok(more) => files.extend(more),
+                Err(_) => break,
+            }
+        }
+        // 取消或失败后只清空队列，让阻塞的遍历线程尽快退出
+        if job.is_cancelled() {
+            continue;
+        }
+        let count = files.len();
+        let res = match shadow {
+            Some(shadow) => index_db.insert_shadow_indexes(shadow, files),
+            None => index_db.insert_file_indexes(files),
+        };
+        match res {
+            Ok(failed) => {
+                let count = count - failed.len();
+                failed.into_iter().for_each(|e| job.add_error(format!("写入索引失败:{}", e)));
+                job.add_inserted(count as u64);
+                let _ = window.emit("file_index_count", count);
+                job.emit_progress(&window);
+            }
+            Err(e) => job.fail(format!("写入索引失败:{:?}", e)),
+        }
+    }
+    index_db
+}
+
+// 多个线程并行遍历，经有界队列交给单个写入线程，避免多个连接争抢写锁导致写入失败；
+// 重建时先写入影子表，全部完成后再换入，期间搜索仍使用原有索引
+fn file_scanning(app_handle: AppHandle, roots: Vec<IndexRoot>, filter: PathFilter, job: Arc<IndexJob>, target: ScanTarget) {
+    let main_window = app_handle.get_window("skylark").unwrap();
+    let shadow = matches!(target, ScanTarget::Rebuild).then(|| format!("{}{}", SHADOW_INDEX_PREFIX, job.id));
+    let (volume_id, index_db) = match &target {
+        ScanTarget::Volume(volume) => match open_volume_index(volume) {
+            Ok(opened) => opened,
+            Err(e) => {
+                job.fail(format!("打开卷索引失败:{:?}", e));
+                job.finish(&main_window);
+                return;
+            }
+        },
+        _ => (0, IndexSQL::new()),
+    };
+    let (tx, rx) = sync_channel::<Vec<FileIndex>>(SCAN_QUEUE_SIZE);
+    let writer = {
+        let window = main_window.clone();
+        let job = job.clone();
+        let shadow = shadow.clone();
+        thread::spawn(move || write_file_indexes(index_db, rx, window, job, shadow.as_deref()))
+    };
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(MAX_SCAN_THREADS);
+    match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| {
+            roots.par_iter().for_each(|root| {
+                println!("开始扫描文件夹:{:?}", root.path);
+                let dirs = split_root(&filter, root, &job, &tx);
+                dirs.par_iter().for_each(|dir| walk_dir(&filter, dir, &job, &tx));
+            });
+        }),
+        Err(e) => job.fail(format!("创建扫描线程失败:{:?}", e)),
+    }
+    // 关闭队列，写入线程写完剩余批次后退出
+    drop(tx);
+    match writer.join() {
+        Ok(mut index_db) => {
+            if let Some(shadow) = &shadow {
+                // 取消或失败时丢弃影子表，原有索引不变
+                if !job.is_cancelled() {
+                    match index_db.swap_shadow_index(shadow, job.started_at) {
+                        Ok(count) => println!("重建索引完成:{:?}", count),
+                        Err(e) => job.fail(format!("换入重建索引失败:{:?}", e)),
+                    }
+                }
+                let _ = index_db.drop_shadow_index(shadow);
+            }
+            if let ScanTarget::Volume(volume) = &target {
+                // 重新挂载时增量更新：扫描只改写新增和变化的条目，已删除的条目在后台清理
+                if !job.is_cancelled() {
+                    IndexVerifier::verify_volume(volume_id, volume.clone());
+                }
+            } else if !job.is_cancelled() {
+                if let Some(indexer) = ContentIndexer::from_config() {
+                    for root in &roots {
+                        indexer.index_pending(&mut index_db, Some(&root.path));
                     }
                 }
             }
         }
+        Err(_) => job.fail("写入线程异常退出".to_string()),
     }
+    job.finish(&main_window);
+}
 
+// 应用索引刷新互斥，手动刷新和目录监听触发的刷新不同时进行
+static APP_INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// 应用所在的目录，应用索引监听这些目录的变化
+pub fn app_dirs() -> Vec<PathBuf> {
+    #[cfg(target_os = "macos")]{
+        vec![
+            PathBuf::from("/System/Applications"),
+            PathBuf::from("/Applications/"),
+            PathBuf::from("/System/Volumes/Preboot/Cryptexes/App/System/Applications"),
+        ]
+    }
     #[cfg(target_os = "windows")]{
-        let drivers = get_drives();
-        println!("扫描所有分区: {:?}", drivers);
-        for driver in drivers {
-            if driver.1 != "Fixed Drive" {
+        //  todo 添加到库
+        let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
+        // result.extend(get_apps(r"C:\Program Files\"));
+        // result.extend(get_apps(r"C:\Program Files (x86)\"));
+        vec![
+            PathBuf::from(r"C:\ProgramData\Microsoft\Windows\Start Menu\Programs\"),
+            PathBuf::from(format!(r"{}\AppData\Roaming\Microsoft\Windows\Start Menu\Programs\", home_dir.as_str())),
+            PathBuf::from(format!(r"{}\Desktop\", home_dir)),
+        ]
+    }
+    #[cfg(target_os = "linux")]{
+        let mut dirs = desktop_entry::application_dirs();
+        dirs.extend(config::Config::new().get_app_search_appimage_paths().into_iter().map(PathBuf::from));
+        dirs
+    }
+}
+
+// 查找已安装的应用，只读取名称和路径等信息，不读取图标
+fn discover_apps() -> Vec<HashMap<String, String>> {
+    let mut result = Vec::new();
+    #[cfg(not(target_os = "linux"))]{
+        for dir in app_dirs() {
+            result.extend(get_apps(&dir.to_string_lossy()));
+        }
+    }
+    #[cfg(target_os = "linux")]{
+        result.extend(desktop_apps(&config::Config::new().get_app_search_appimage_paths()));
+    }
+    result
+}
+
+// 应用文件（.app、.lnk、.desktop、AppImage）的修改时间，应用更新后随之变化
+fn app_mtime(path: &str) -> i64 {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |time| time.as_secs() as i64)
+}
+
+// 读取应用图标并生成索引条目
+fn build_app_index(app: &HashMap<String, String>, mtime: i64) -> FileIndex {
+    let title = app.get("title").unwrap().to_string();
+    let data = app.get("data").unwrap();
+    let desc = app.get("desc").unwrap();
+    let (pinyin, abb) = text_to_pinyin(&title);
+    #[cfg(target_os = "linux")]
+    let keywords = keyword_words(app.get("keywords").map_or("", String::as_str));
+    #[cfg(not(target_os = "linux"))]
+    let keywords = String::new();
+    let mut icon_base64 = String::new();
+    #[cfg(target_os = "macos")]{
+        let local_icon_file = vec!["日历", "迁移助理", "Photo Booth", "系统信息", "系统设置"];
+        let mut icon_file_path = String::new();
+        if !local_icon_file.contains(&title.as_str()) {
+            icon_file_path = read_app_info(&data);
+        } else {
+            icon_file_path = format!("icons/{}.png", title);
+        }
+        icon_base64 = match read_icns_to_base64(&icon_file_path) {
+            Ok(base64) => { base64 }
+            Err(e) => {
+                println!("错误 {}", e);
+                "".to_string()
+            }
+        }
+    }
+    #[cfg(target_os = "linux")]{
+        if let Some(icon_path) = find_linux_icon(app.get("icon").unwrap()) {
+            icon_base64 = read_file_to_base64(icon_path.to_str().unwrap());
+        }
+    }
+    #[cfg(target_os = "windows")]{
+        //todo 获取应用图标
+        icon_base64 = read_icon_to_base64(desc.to_string());
+        println!("{}===>{}", data, title);
+    }
+    FileIndex {
+        title: title.clone(),
+        path: data.to_string(),
+        desc: desc.to_string(),
+        icon: icon_base64,
+        pinyin,
+        abb,
+        file_type: app.get("type").unwrap().to_string(),
+        mtime,
+        keywords,
+        ..Default::default()
+    }
+}
+
+/// 增量刷新应用索引：按路径与已索引的应用比较，新增和有变化的应用重新读取图标，已卸载的删除（无法读取的应用目录中的除外），其余保持不变。
+/// 更新在原有记录上进行，id 不变，刷新期间搜索不受影响
+pub fn refresh_app_index() {
+    let _guard = APP_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index_db = IndexSQL::new();
+    let existing: HashMap<String, FileIndex> = match index_db.find_app_rows() {
+        Ok(rows) => rows.into_iter().map(|row| (row.path.clone(), row)).collect(),
+        Err(e) => {
+            println!("读取应用索引失败:{:?}", e);
+            return;
+        }
+    };
+    // 无法读取的目录（权限变化、未挂载的磁盘）中的应用查找不到，不能当作已卸载
+    let unreadable: Vec<PathBuf> = app_dirs().into_iter().filter(|dir| fs::read_dir(dir).is_err()).collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed = vec![];
+    for app in discover_apps() {
+        let path = app.get("data").unwrap().to_string();
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let mtime = app_mtime(&path);
+        if let Some(row) = existing.get(&path) {
+            if row.title == app["title"] && row.file_type == app["type"] && row.mtime == mtime {
                 continue;
             }
-            println!("扫描到分区: {:?}", driver.0);
-            let skip_paths = config.local_file_search_exclude_paths.clone();
-            let skip_extensions = config.local_file_search_exclude_types.clone();
-            file_scanning(app_handle.clone(), &driver.0, skip_paths, skip_extensions);
+        }
+        changed.push(build_app_index(&app, mtime));
+    }
+    let removed: Vec<u64> = existing.values()
+        .filter(|row| !seen.contains(&row.path) && !unreadable.iter().any(|dir| Path::new(&row.path).starts_with(dir)))
+        .map(|row| row.id).collect();
+    println!("应用索引更新：新增或变化 {} 个，删除 {} 个", changed.len(), removed.len());
+    if !changed.is_empty() {
+        if let Err(e) = index_db.insert_app_indexes(changed) {
+            println!("更新应用索引失败:{:?}", e);
+        }
+    }
+    if !removed.is_empty() {
+        if let Err(e) = index_db.delete_by_ids("app", &removed) {
+            println!("删除应用索引失败:{:?}", e);
+        }
+    }
+}
+
+pub fn create_app_index_to_sql() {
+    refresh_app_index();
+}
+
+/// 扫描全部索引根目录，返回索引任务 id；已有任务运行时返回该任务的 id
+pub fn create_file_index_to_sql(app_handle: AppHandle) -> Option<u64> {
+    scan_index_roots(app_handle, file_index_roots(), ScanTarget::Index)
+}
+
+/// 重建文件索引，扫描完成后整体替换原有索引，不在索引根目录下的条目一并删除
+pub fn rebuild_file_index_to_sql(app_handle: AppHandle) -> Option<u64> {
+    scan_index_roots(app_handle, file_index_roots(), ScanTarget::Rebuild)
+}
+
+/// 重新扫描单个索引根目录，用于定时扫描
+pub fn rescan_index_root(app_handle: AppHandle, root: &IndexRoot) -> Option<u64> {
+    scan_index_roots(app_handle, vec![root.clone()], ScanTarget::Index)
+}
+
+/// 刷新卷索引，返回任务 id；已有任务运行时返回 Err(运行中的任务 id)
+pub fn refresh_volume_index(app_handle: AppHandle, volume: &Volume) -> Result<u64, u64> {
+    let root = IndexRoot { path: volume.path.clone(), ..Default::default() };
+    start_scan(app_handle, vec![root], ScanTarget::Volume(volume.clone()))
+}
+
+fn scan_index_roots(app_handle: AppHandle, roots: Vec<IndexRoot>, target: ScanTarget) -> Option<u64> {
+    if roots.is_empty() {
+        return None;
+    }
+    match start_scan(app_handle, roots, target) {
+        Ok(job_id) => Some(job_id),
+        Err(running) => {
+            println!("索引任务运行中:{:?}", running);
+            Some(running)
         }
     }
 }
 
+fn start_scan(app_handle: AppHandle, roots: Vec<IndexRoot>, target: ScanTarget) -> Result<u64, u64> {
+    let job = IndexJob::start(roots.iter().map(|root| root.path.clone()).collect()).map_err(|running| running.id)?;
+    let job_id = job.id;
+    let filter = PathFilter::from_config();
+    thread::spawn(move || file_scanning(app_handle, roots, filter, job, target));
+    Ok(job_id)
+}
+
 
+#[cfg(target_os = "windows")]
 pub(crate) fn is_process_running(process_name: &str) -> bool {
     let mut snapshot: HANDLE = unsafe { CreateToolhelp32Snapshot(0x2, 0) }; // TH32CS_SNAPALL
     let mut process_entry: PROCESSENTRY32 = unsafe { std::mem::zeroed() };
@@ -742,6 +1436,7 @@ pub(crate) fn is_process_running(process_name: &str) -> bool {
     false
 }
 
+#[cfg(target_os = "windows")]
 fn open_or_activate_app(process_name: &str, app_name: &str) {
     let current_dir = Path::new(process_name).parent().unwrap();
     let program = process_name.split("\\").last().expect("aa.exe");
@@ -800,6 +1495,7 @@ fn open_or_activate_app(process_name: &str, app_name: &str) {
     }
 }
 
+#[cfg(target_os = "windows")]
 unsafe extern "system" fn enum_window_proc(hwnd: HWND, l_param: LPARAM) -> i32 {
     let search_text = l_param as *const u16;
     let search_osstr = OsString::from_wide(std::slice::from_raw_parts(search_text, 20)); // 假设长度为 20
@@ -838,6 +1534,7 @@ unsafe extern "system" fn enum_window_proc(hwnd: HWND, l_param: LPARAM) -> i32 {
     }
     1 // 继续枚举
 }
+#[cfg(target_os = "windows")]
 pub(crate) fn find_windows_with_partial_title(partial_title: &str) {
     let partial_title = &(partial_title.to_owned() + "###");
     let wide_partial_title: Vec<u16> = OsStr::new(partial_title)
@@ -850,6 +1547,7 @@ pub(crate) fn find_windows_with_partial_title(partial_title: &str) {
     }
 }
 #[test]
+#[cfg(target_os = "windows")]
 #[allow(unused)]
 fn test1() {
     // use chrono::Duration;
@@ -0,0 +1,196 @@
+use chrono::Local;
+use serde::Deserialize;
+use crate::api::verifier::is_missing;
+use crate::utils::database::{FileIndex, IndexSQL, Launch};
+
+// 启动记录排序（frecency）：每次启动按距今时间衰减后累加，同一查询词下的启动额外加权，
+// 常用的简短查询（如 ps）会直接映射到经常选中的结果，即使该结果不在检索结果中
+
+/// 应用搜索的启动记录
+pub const APP_PROVIDER: &str = "app";
+/// 文件名、内容、最近文件和重复文件搜索共用的启动记录
+pub const FILE_PROVIDER: &str = "file";
+// 只保留最近 HISTORY_DAYS 天的启动记录
+const HISTORY_DAYS: i64 = 180;
+// 启动记录的权重每过 HALF_LIFE_DAYS 天减半
+const HALF_LIFE_DAYS: f64 = 14.0;
+// 查询词相同的启动记录的额外权重，查询词以当前输入开头时减半
+const QUERY_BOOST: f64 = 4.0;
+// 按查询词映射补充到第一页的结果数
+const LEARNED_LIMIT: usize = 3;
+// 启动记录得分取对数后乘以该系数加在匹配得分上，约为一个字符命中的得分，常用的结果靠前但不会越过匹配明显更好的结果
+const FRECENCY_WEIGHT: f64 = 16.0;
+// 已按相关度排列的结果以位置折算匹配得分，相邻两项相差的得分
+const POSITION_SCORE: i64 = 4;
+
+fn normalize(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+pub struct Frecency {
+    query: String,
+    launches: Vec<Launch>,
+    now: i64,
+}
+
+impl Frecency {
+    pub fn load(provider: &str, query: &str) -> Self {
+        let now = Local::now().timestamp();
+        let launches = IndexSQL::new().find_launches(provider, now - HISTORY_DAYS * 86400).unwrap_or_else(|e| {
+            println!("读取启动记录失败:{:?}", e);
+            vec![]
+        });
+        Self::new(query, launches, now)
+    }
+
+    fn new(query: &str, launches: Vec<Launch>, now: i64) -> Self {
+        Self { query: normalize(query), launches, now }
+    }
+
+    // 启动记录的查询词与当前输入的匹配程度：相同为 1，以当前输入开头为 0.5
+    fn query_match(&self, launch: &Launch) -> f64 {
+        if self.query.is_empty() {
+            0.0
+        } else if launch.query == self.query {
+            1.0
+        } else if launch.query.starts_with(&self.query) {
+            0.5
+        } else {
+            0.0
+        }
+    }
+
+    pub fn score(&self, result_id: &str) -> f64 {
+        self.launches.iter().filter(|launch| launch.result_id == result_id).map(|launch| {
+            let age_days = (self.now - launch.time).max(0) as f64 / 86400.0;
+            0.5f64.powf(age_days / HALF_LIFE_DAYS) * (1.0 + QUERY_BOOST * self.query_match(launch))
+        }).sum()
+    }
+
+    /// 在当前查询词下选中过的结果，按得分从高到低排列
+    pub fn learned(&self) -> Vec<String> {
+        let mut ids: Vec<&str> = vec![];
+        for launch in self.launches.iter().filter(|launch| self.query_match(launch) == 1.0) {
+            if !ids.contains(&launch.result_id.as_str()) {
+                ids.push(&launch.result_id);
+            }
+        }
+        let mut scored: Vec<(String, f64)> = ids.into_iter().map(|id| (id.to_string(), self.score(id))).collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(LEARNED_LIMIT).map(|(id, _)| id).collect()
+    }
+
+    /// 启动记录得分折算后加在匹配得分上，按合计得分稳定排序；没有启动记录时保持原有顺序
+    pub fn rank(&self, items: &mut Vec<FileIndex>) {
+        if self.launches.is_empty() {
+            return;
+        }
+        let mut scored: Vec<(f64, FileIndex)> = items.drain(..).map(|item| {
+            (item.score as f64 + FRECENCY_WEIGHT * self.score(&item.path).ln_1p(), item)
+        }).collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        items.extend(scored.into_iter().map(|(_, item)| item));
+    }
+
+    /// 已按相关度排列、得分不可比较的结果（数据库分页、内容和重复文件搜索），以位置作为匹配得分再排序
+    pub fn rank_in_order(&self, items: &mut Vec<FileIndex>) {
+        let count = items.len() as i64;
+        for (index, item) in items.iter_mut().enumerate() {
+            item.score = (count - index as i64) * POSITION_SCORE;
+        }
+        self.rank(items);
+    }
+
+    /// 排序分页返回的结果：第一页在最前补充按查询词映射的结果，之后的页去掉这些结果以免重复；table 为补充结果所在的索引表
+    pub fn rank_page(&self, items: &mut Vec<FileIndex>, offset: i32, table: &str) {
+        let db = IndexSQL::new();
+        if offset > 0 {
+            // 只去掉第一页实际补充的结果：分页结果互不重复，出现在本页的路径不在第一页中，
+            // 第一页按同样的条件补充了它，已删除或索引中查不到的仍保留在原位置
+            let added: Vec<String> = self.learned().into_iter()
+                .filter(|path| items.iter().any(|item| &item.path == path))
+                .filter(|path| !is_missing(path) && matches!(db.find_by_path(table, path), Ok(Some(_))))
+                .collect();
+            items.retain(|item| !added.contains(&item.path));
+        } else {
+            let mut added = vec![];
+            for path in self.learned() {
+                if items.iter().any(|item| item.path == path) || is_missing(&path) {
+                    continue;
+                }
+                if let Ok(Some(item)) = db.find_by_path(table, &path) {
+                    added.push(item);
+                }
+            }
+            items.splice(0..0, added);
+        }
+        self.rank_in_order(items);
+    }
+}
+
+/// 记录一次启动：query 为输入的查询词，provider 为 app、file 或 component，result_id 为结果路径或插件组件标题
+#[tauri::command(rename_all = "camelCase")]
+pub fn record_launch(query: &str, provider: &str, result_id: &str) {
+    let now = Local::now().timestamp();
+    if let Err(e) = IndexSQL::new().insert_launch(&normalize(query), provider, result_id, now, now - HISTORY_DAYS * 86400) {
+        println!("保存启动记录失败:{:?}", e);
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchTarget {
+    provider: String,
+    result_id: String,
+}
+
+/// 前端合并插件组件和搜索结果后，按各自的启动记录得分排序
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_frecency_scores(query: &str, targets: Vec<LaunchTarget>) -> Vec<f64> {
+    let mut providers: Vec<(String, Frecency)> = vec![];
+    targets.iter().map(|target| {
+        if !providers.iter().any(|(provider, _)| *provider == target.provider) {
+            providers.push((target.provider.clone(), Frecency::load(&target.provider, query)));
+        }
+        let (_, frecency) = providers.iter().find(|(provider, _)| *provider == target.provider).unwrap();
+        frecency.score(&target.result_id)
+    }).collect()
+}
+
+#[test]
+fn test_frecency() {
+    let now = 1_700_000_000;
+    let day = 86400;
+    let launch = |query: &str, result_id: &str, days: i64| Launch { query: query.to_string(), result_id: result_id.to_string(), time: now - days * day };
+    let launches = vec![
+        launch("ps", "/opt/photoshop", 1),
+        launch("ps", "/opt/photoshop", 20),
+        launch("pho", "/opt/photos", 0),
+        launch("", "/opt/photos", 0),
+        launch("term", "/opt/konsole", 2),
+    ];
+    let frecency = Frecency::new(" PS ", launches.clone(), now);
+    assert_eq!(frecency.learned(), vec!["/opt/photoshop"]);
+    // 当天启动一次权重为 1，14 天前减半
+    let expected = 0.5f64.powf(1.0 / 14.0) * 5.0 + 0.5f64.powf(20.0 / 14.0) * 5.0;
+    assert!((frecency.score("/opt/photoshop") - expected).abs() < 1e-9);
+    assert_eq!(frecency.score("/opt/photos"), 2.0);
+
+    // 启动记录只加在匹配得分上，匹配明显更好的结果仍在前
+    let item = |path: &str, score: i64| FileIndex { path: path.to_string(), score, ..Default::default() };
+    let mut items = vec![item("/opt/a", 100), item("/opt/photos", 60), item("/opt/b", 60), item("/opt/photoshop", 50)];
+    frecency.rank(&mut items);
+    let paths: Vec<&str> = items.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, vec!["/opt/a", "/opt/photoshop", "/opt/photos", "/opt/b"]);
+
+    let mut items = vec![item("/opt/a", 0), item("/opt/b", 0), item("/opt/photos", 0)];
+    frecency.rank_in_order(&mut items);
+    let paths: Vec<&str> = items.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, vec!["/opt/photos", "/opt/a", "/opt/b"]);
+
+    // 输入 p 时，查询词以 p 开头的启动记录得分减半
+    let frecency = Frecency::new("p", launches, now);
+    assert!(frecency.learned().is_empty());
+    assert_eq!(frecency.score("/opt/photos"), 4.0);
+    assert_eq!(frecency.score("/opt/konsole"), 0.5f64.powf(2.0 / 14.0));
+}
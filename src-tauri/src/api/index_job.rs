@@ -0,0 +1,206 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::Window;
+
+// 文件索引任务：记录扫描进度，支持取消，通过 index_job_progress、index_job_finished 事件通知前端
+
+// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+// 保留的已结束任务数
+const MAX_FINISHED_JOBS: usize = 20;
+
+static JOBS: Mutex<Vec<Arc<IndexJob>>> = Mutex::new(Vec::new());
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexJobStatus {
+    pub id: u64,
+    pub roots: Vec<String>,
+    pub state: JobState,
+    /// 遍历到的条目数
+    pub scanned: u64,
+    /// 写入索引的条目数
+    pub inserted: u64,
+    /// 被排除规则跳过的条目数，跳过的目录按一条计
+    pub skipped: u64,
+    pub errors: u64,
+    pub last_error: Option<String>,
+    /// 开始时间，秒级时间戳
+    pub started_at: i64,
+    pub elapsed_ms: u64,
+    /// 每秒扫描的条目数
+    pub throughput: f64,
+}
+
+#[derive(Debug)]
+pub struct IndexJob {
+    pub id: u64,
+    roots: Vec<String>,
+    cancelled: AtomicBool,
+    state: Mutex<JobState>,
+    scanned: AtomicU64,
+    inserted: AtomicU64,
+    pub skipped: Arc<AtomicU64>,
+    errors: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    started: Instant,
+    /// 开始时间，秒级时间戳
+    pub started_at: i64,
+    finished: Mutex<Option<Instant>>,
+    last_progress: Mutex<Instant>,
+}
+
+impl IndexJob {
+    /// 创建并登记任务，已有未取消的任务运行时返回 Err(运行中的任务)
+    pub fn start(roots: Vec<String>) -> Result<Arc<IndexJob>, Arc<IndexJob>> {
+        let mut jobs = JOBS.lock().unwrap();
+        // 已取消的任务会在当前条目处理完后停止，不阻止新任务
+        if let Some(job) = jobs.iter().find(|job| job.state() == JobState::Running && !job.is_cancelled()) {
+            return Err(job.clone());
+        }
+        let now = Instant::now();
+        let job = Arc::new(IndexJob {
+            id: NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst),
+            roots,
+            cancelled: AtomicBool::new(false),
+            state: Mutex::new(JobState::Running),
+            scanned: AtomicU64::new(0),
+            inserted: AtomicU64::new(0),
+            skipped: Arc::new(AtomicU64::new(0)),
+            errors: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            started: now,
+            started_at: Local::now().timestamp(),
+            finished: Mutex::new(None),
+            last_progress: Mutex::new(now),
+        });
+        // 只保留最近结束的任务
+        while jobs.len() >= MAX_FINISHED_JOBS {
+            match jobs.iter().position(|job| job.state() != JobState::Running) {
+                Some(index) => jobs.remove(index),
+                None => break,
+            };
+        }
+        jobs.push(job.clone());
+        Ok(job)
+    }
+
+    pub fn find(id: u64) -> Option<Arc<IndexJob>> {
+        JOBS.lock().unwrap().iter().find(|job| job.id == id).cloned()
+    }
+
+    pub fn all() -> Vec<Arc<IndexJob>> {
+        JOBS.lock().unwrap().clone()
+    }
+
+    pub fn state(&self) -> JobState {
+        *self.state.lock().unwrap()
+    }
+
+    pub fn cancel(&self) -> bool {
+        if self.state() != JobState::Running {
+            return false;
+        }
+        self.cancelled.store(true, Ordering::SeqCst);
+        true
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn add_scanned(&self, count: u64) {
+        self.scanned.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_inserted(&self, count: u64) {
+        self.inserted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_error(&self, error: String) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some(error);
+    }
+
+    /// 写入数据库失败等无法继续的错误，任务以失败结束
+    pub fn fail(&self, error: String) {
+        self.add_error(error);
+        *self.state.lock().unwrap() = JobState::Failed;
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn status(&self) -> IndexJobStatus {
+        let elapsed = self.finished.lock().unwrap().unwrap_or_else(Instant::now) - self.started;
+        let scanned = self.scanned.load(Ordering::Relaxed);
+        let seconds = elapsed.as_secs_f64();
+        IndexJobStatus {
+            id: self.id,
+            roots: self.roots.clone(),
+            state: self.state(),
+            scanned,
+            inserted: self.inserted.load(Ordering::Relaxed),
+            skipped: self.skipped.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().unwrap().clone(),
+            started_at: self.started_at,
+            elapsed_ms: elapsed.as_millis() as u64,
+            throughput: if seconds > 0.0 { scanned as f64 / seconds } else { 0.0 },
+        }
+    }
+
+    /// 发送进度事件，间隔不足时忽略
+    pub fn emit_progress(&self, window: &Window) {
+        {
+            let mut last = self.last_progress.lock().unwrap();
+            if last.elapsed() < PROGRESS_INTERVAL {
+                return;
+            }
+            *last = Instant::now();
+        }
+        let _ = window.emit("index_job_progress", self.status());
+    }
+
+    /// 遍历和写入全部结束，发送汇总事件
+    pub fn finish(&self, window: &Window) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if *state == JobState::Running {
+                *state = if self.is_cancelled() { JobState::Cancelled } else { JobState::Completed };
+            }
+        }
+        *self.finished.lock().unwrap() = Some(Instant::now());
+        let status = self.status();
+        println!("索引任务结束:{:?}", status);
+        let _ = window.emit("index_job_finished", status);
+    }
+}
+
+/// 取消索引任务，不传 id 时取消所有运行中的任务
+#[tauri::command(rename_all = "camelCase")]
+pub fn cancel_index_job(job_id: Option<u64>) -> bool {
+    match job_id {
+        Some(id) => IndexJob::find(id).is_some_and(|job| job.cancel()),
+        None => IndexJob::all().iter().fold(false, |cancelled, job| job.cancel() || cancelled),
+    }
+}
+
+/// 查询索引任务状态，不传 id 时返回最近的全部任务
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_index_job_status(job_id: Option<u64>) -> Vec<IndexJobStatus> {
+    match job_id {
+        Some(id) => IndexJob::find(id).map(|job| job.status()).into_iter().collect(),
+        None => IndexJob::all().iter().map(|job| job.status()).collect(),
+    }
+}
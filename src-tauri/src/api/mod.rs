@@ -3,5 +3,15 @@ pub mod shell;
 pub mod tray;
 pub mod clipboard;
 pub mod proxy_pool;
+pub mod watcher;
+pub mod content;
+pub mod scheduler;
+pub mod index_job;
+pub mod verifier;
+pub mod volume_monitor;
+pub mod duplicates;
+pub mod recent;
+pub mod frecency;
+pub mod alias;
 pub mod rclip;
 pub mod wclip;
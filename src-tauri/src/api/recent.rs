@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::Path;
+use chrono::{DateTime, Local};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::api::explorer::build_file_index;
+use crate::api::verifier::is_missing;
+use crate::utils::database::{FileIndex, IndexSQL};
+
+// 最近文件：索引根目录下最近修改的文件与 recently-used.xbel 中最近打开的文件合并，按时间倒序
+
+/// 最近文件关键词，如 recent、recent 报告
+pub const RECENT_KEYWORD: &str = "recent";
+// 只列出最近 RECENT_DAYS 天内修改或打开的文件
+const RECENT_DAYS: i64 = 30;
+// 从文件索引中读取的最近修改文件数
+const INDEX_LIMIT: usize = 300;
+const PAGE_SIZE: usize = 30;
+
+/// 输入为 recent 或以 recent 加空格开头时，返回其后的过滤词
+pub fn strip_recent_keyword(input: &str) -> Option<&str> {
+    let rest = input.trim_start().strip_prefix(RECENT_KEYWORD)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+// file:// 地址转为本地路径，其他主机上的文件和其他协议返回 None
+fn file_url_to_path(url: &str) -> Option<String> {
+    let path = url.strip_prefix("file://")?;
+    if !path.starts_with('/') {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if bytes[i] == b'%' => {
+                result.push(byte);
+                i += 3;
+            }
+            _ => {
+                result.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(String::from_utf8_lossy(&result).to_string())
+}
+
+/// 解析 recently-used.xbel，返回 (本地路径, 添加、修改、访问时间中最晚的一个)
+fn parse_xbel(xml: &[u8]) -> Vec<(String, i64)> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut entries = vec![];
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"bookmark" => {
+                let mut path = None;
+                let mut time = 0;
+                for attr in e.attributes().filter_map(Result::ok) {
+                    let value = match attr.unescape_value() {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+                    match attr.key.as_ref() {
+                        b"href" => path = file_url_to_path(&value),
+                        b"added" | b"modified" | b"visited" => {
+                            if let Ok(t) = DateTime::parse_from_rfc3339(&value) {
+                                time = time.max(t.timestamp());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(path) = path {
+                    entries.push((path, time));
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                println!("解析最近使用的文件失败:{:?}", e);
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    entries
+}
+
+// GTK 等遵循 XDG 规范的应用记录的最近使用文件
+fn recently_used() -> Vec<(String, i64)> {
+    #[cfg(target_os = "linux")]{
+        if let Some(data_dir) = tauri::api::path::data_dir() {
+            if let Ok(xml) = std::fs::read(data_dir.join("recently-used.xbel")) {
+                return parse_xbel(&xml);
+            }
+        }
+    }
+    vec![]
+}
+
+/// 最近文件，keyword 不为空时只返回文件名包含关键词的文件，已不存在的文件不返回
+pub fn search_recent_files(keyword: &str, offset: i32) -> Vec<FileIndex> {
+    let since = Local::now().timestamp() - RECENT_DAYS * 86400;
+    let mut recent: HashMap<String, (i64, Option<FileIndex>)> = HashMap::new();
+    match IndexSQL::new().find_recent_files(since, INDEX_LIMIT) {
+        Ok(items) => {
+            for item in items {
+                recent.insert(item.path.clone(), (item.mtime, Some(item)));
+            }
+        }
+        Err(e) => println!("读取最近修改的文件失败:{:?}", e),
+    }
+    for (path, time) in recently_used() {
+        if time < since {
+            continue;
+        }
+        let entry = recent.entry(path).or_insert((time, None));
+        entry.0 = entry.0.max(time);
+    }
+    let keyword = keyword.to_lowercase();
+    let mut recent: Vec<(String, (i64, Option<FileIndex>))> = recent.into_iter()
+        .filter(|(path, _)| {
+            keyword.is_empty() || Path::new(path).file_name()
+                .is_some_and(|name| name.to_string_lossy().to_lowercase().contains(&keyword))
+        })
+        .collect();
+    recent.sort_by(|a, b| (b.1).0.cmp(&(a.1).0));
+    recent.into_iter()
+        .filter(|(path, _)| !is_missing(path))
+        .skip(offset.max(0) as usize)
+        .take(PAGE_SIZE)
+        .map(|(path, (_, item))| item.unwrap_or_else(|| build_file_index(Path::new(&path))))
+        .collect()
+}
+
+#[test]
+fn test_parse_xbel() {
+    let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark href="file:///home/user/%E6%8A%A5%E5%91%8A%20v2.pdf" added="2024-05-01T10:00:00Z" modified="2024-05-02T10:00:00.123456Z" visited="2024-05-01T11:00:00Z">
+    <info><metadata owner="http://freedesktop.org"><mime:mime-type type="application/pdf"/></metadata></info>
+  </bookmark>
+  <bookmark href="sftp://host/home/user/a.txt" added="2024-05-01T10:00:00Z"/>
+  <bookmark href="file:///tmp/a&amp;b.txt" visited="2024-05-03T00:00:00Z"/>
+</xbel>"#;
+    let entries = parse_xbel(xml);
+    assert_eq!(entries, vec![
+        ("/home/user/报告 v2.pdf".to_string(), 1714644000),
+        ("/tmp/a&b.txt".to_string(), 1714694400),
+    ]);
+    assert_eq!(strip_recent_keyword("recent"), Some(""));
+    assert_eq!(strip_recent_keyword(" recent 报告 "), Some("报告"));
+    assert_eq!(strip_recent_keyword("recently"), None);
+}
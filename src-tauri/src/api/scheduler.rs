@@ -0,0 +1,36 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::api::explorer::{file_index_roots, rescan_index_root};
+
+// 检查是否到期的间隔
+const TICK: Duration = Duration::from_secs(60);
+
+/// 定时扫描，按 index_roots 中设置的 rescan_interval（分钟）重新扫描对应根目录
+pub struct RescanScheduler;
+
+impl RescanScheduler {
+    pub fn start(app_handle: AppHandle) {
+        let roots: Vec<_> = file_index_roots().into_iter()
+            .filter(|root| root.rescan_interval.is_some_and(|minutes| minutes > 0))
+            .collect();
+        if roots.is_empty() {
+            return;
+        }
+        thread::spawn(move || {
+            let interval = |minutes: Option<u64>| Duration::from_secs(minutes.unwrap_or(0) * 60);
+            let mut next: Vec<Instant> = roots.iter().map(|root| Instant::now() + interval(root.rescan_interval)).collect();
+            loop {
+                thread::sleep(TICK);
+                let now = Instant::now();
+                for (root, next) in roots.iter().zip(next.iter_mut()) {
+                    if now >= *next {
+                        println!("定时扫描文件夹:{:?}", root.path);
+                        rescan_index_root(app_handle.clone(), root);
+                        *next = now + interval(root.rescan_interval);
+                    }
+                }
+            }
+        });
+    }
+}
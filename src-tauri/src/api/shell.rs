@@ -15,14 +15,21 @@ use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
+#[cfg(target_os = "windows")]
 use std::os::windows::ffi::OsStrExt;
+#[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use std::{path, ptr};
 use std::path::Path;
 use std::process::{Command, Stdio};
 use webbrowser;
 use anyhow::Result;
+#[cfg(target_os = "windows")]
 use winapi::um::processthreadsapi::{CreateProcessW, PROCESS_INFORMATION, STARTUPINFOW};
+#[cfg(target_os = "linux")]
+use crate::utils::appimage;
+#[cfg(target_os = "linux")]
+use crate::utils::desktop_entry::{find_executable, DesktopEntry};
 
 #[tauri::command(rename_all = "camelCase")]
 pub fn run_python_script(script_path: &str, params: Vec<String>) -> HashMap<&str, String> {
@@ -129,6 +136,80 @@ pub fn open_app(app_path: &str, app_name: &str) {
     }
 }
 
+#[tauri::command(rename_all = "camelCase")]
+#[cfg(target_os = "linux")]
+pub fn open_app(app_path: &str, app_name: &str) {
+    println!("打开app:{:?}", app_path);
+    let path = Path::new(app_path);
+    if path.extension().is_some_and(|ext| ext == "desktop") {
+        // 按桌面项的 Exec 启动，不把 .desktop 文件当作文档打开
+        if let Err(e) = launch_desktop_entry(path) {
+            eprintln!("Failed to launch app {}: {}", app_name, e);
+            // 通过 desktop 文件 id 启动应用
+            let desktop_id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let _ = Command::new("gtk-launch").arg(&desktop_id).spawn();
+        }
+        return;
+    }
+    if appimage::appimage_type(path).is_some() {
+        // AppImage 直接执行，不交给默认程序打开
+        if let Err(e) = launch_appimage(path) {
+            eprintln!("Failed to launch app {}: {}", app_name, e);
+        }
+        return;
+    }
+    if let Err(e) = that(path) {
+        eprintln!("Failed to open app {}: {}", app_name, e);
+    }
+}
+
+// 常见的终端程序及其执行命令的参数
+#[cfg(target_os = "linux")]
+const TERMINALS: [(&str, &str); 4] = [("x-terminal-emulator", "-e"), ("gnome-terminal", "--"), ("konsole", "-e"), ("xterm", "-e")];
+
+#[cfg(target_os = "linux")]
+fn launch_desktop_entry(path: &Path) -> Result<()> {
+    let entry = DesktopEntry::load(path).ok_or_else(|| anyhow::anyhow!("无法读取桌面项:{:?}", path))?;
+    let mut args = entry.expand_exec(&[]).ok_or_else(|| anyhow::anyhow!("无效的 Exec:{}", entry.exec))?;
+    if entry.terminal {
+        let (terminal, flag) = TERMINALS.iter()
+            .find(|(terminal, _)| find_executable(terminal).is_some())
+            .ok_or_else(|| anyhow::anyhow!("没有可用的终端"))?;
+        args.splice(0..0, [terminal.to_string(), flag.to_string()]);
+    }
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    if !entry.working_dir.is_empty() && Path::new(&entry.working_dir).is_dir() {
+        command.current_dir(&entry.working_dir);
+    }
+    spawn_detached(command)
+}
+
+// 下载的 AppImage 通常没有执行权限，启动前为当前用户补上
+#[cfg(target_os = "linux")]
+fn launch_appimage(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    if permissions.mode() & 0o100 == 0 {
+        println!("为 AppImage 添加执行权限:{:?}", path);
+        permissions.set_mode(permissions.mode() | 0o100);
+        fs::set_permissions(path, permissions)?;
+    }
+    let mut command = Command::new(path);
+    command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    if let Some(dir) = path.parent() {
+        command.current_dir(dir);
+    }
+    spawn_detached(command)
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_detached(mut command: Command) -> Result<()> {
+    let mut child = command.spawn()?;
+    // 等待子进程退出并回收，避免留下僵尸进程
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
 
 #[tauri::command(rename_all = "camelCase")]
 pub fn open_url(url: &str) {
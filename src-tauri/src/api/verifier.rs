@@ -0,0 +1,114 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use anyhow::{bail, Result};
+use crate::api::explorer::build_file_index;
+use crate::utils::database::IndexSQL;
+use crate::utils::volumes::{self, Volume};
+
+// 每块校验的条目数
+const CHUNK_SIZE: usize = 500;
+// 两块之间的间隔，避免长时间占用磁盘和写锁
+const CHUNK_PAUSE: Duration = Duration::from_millis(200);
+// 启动后先让首次扫描和内容索引执行
+const START_DELAY: Duration = Duration::from_secs(60);
+// 每轮校验的间隔
+const ROUND_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Default)]
+pub struct VerifyResult {
+    pub checked: usize,
+    pub removed: usize,
+    pub refreshed: usize,
+}
+
+/// 路径已不存在，无权限等其他错误不算不存在
+pub fn is_missing(path: &str) -> bool {
+    fs::symlink_metadata(path).map_or_else(|e| e.kind() == ErrorKind::NotFound, |_| false)
+}
+
+// 卷仍挂载在原路径上
+fn is_mounted(volume: &Volume) -> bool {
+    Path::new(&volume.path).exists()
+        && volumes::mounted().iter().any(|mounted| mounted.uuid == volume.uuid && mounted.path == volume.path)
+}
+
+/// 后台校验索引：分块检查路径是否存在以及修改时间、大小是否变化，删除已不存在的条目，刷新已变化的条目
+pub struct IndexVerifier;
+
+impl IndexVerifier {
+    pub fn start() {
+        thread::spawn(|| {
+            thread::sleep(START_DELAY);
+            loop {
+                let mut index_db = IndexSQL::new();
+                for table in ["file", "app"] {
+                    match Self::verify(&mut index_db, table, None) {
+                        Ok(result) => println!("索引校验完成:{} {:?}", table, result),
+                        Err(e) => println!("索引校验失败:{} {:?}", table, e),
+                    }
+                }
+                drop(index_db);
+                thread::sleep(ROUND_INTERVAL);
+            }
+        });
+    }
+
+    /// 卷重新扫描后在后台清理已删除的条目
+    pub fn verify_volume(volume_id: i64, volume: Volume) {
+        thread::spawn(move || {
+            let mut index_db = IndexSQL::open_volume(volume_id, &volume.uuid);
+            match Self::verify(&mut index_db, "file", Some(&volume)) {
+                Ok(result) => println!("卷索引校验完成:{} {:?}", volume.path, result),
+                Err(e) => println!("卷索引校验失败:{} {:?}", volume.path, e),
+            }
+        });
+    }
+
+    /// 校验一张索引表，应用索引只检查是否存在；volume 为所校验的卷，卷被卸载时停止，避免把整个卷的条目当作已删除
+    pub fn verify(index_db: &mut IndexSQL, table: &str, volume: Option<&Volume>) -> Result<VerifyResult> {
+        let mut result = VerifyResult::default();
+        let mut after_id = 0;
+        loop {
+            let chunk = index_db.find_index_chunk(table, after_id, CHUNK_SIZE)?;
+            if chunk.is_empty() {
+                break;
+            }
+            after_id = chunk[chunk.len() - 1].id;
+            result.checked += chunk.len();
+            let mut removed = vec![];
+            let mut changed = vec![];
+            for item in chunk {
+                // 不是路径的条目无法校验
+                if !Path::new(&item.path).is_absolute() {
+                    continue;
+                }
+                if is_missing(&item.path) {
+                    removed.push(item.id);
+                    continue;
+                }
+                if table == "file" {
+                    let current = build_file_index(Path::new(&item.path));
+                    if (current.size, current.mtime, current.is_dir) != (item.size, item.mtime, item.is_dir) {
+                        changed.push(current);
+                    }
+                }
+            }
+            // 固定的挂载点在卸载后仍然存在，还要确认卷仍在已挂载的卷中
+            if volume.is_some_and(|volume| !is_mounted(volume)) {
+                bail!("卷已卸载");
+            }
+            if !removed.is_empty() {
+                result.removed += index_db.delete_by_ids(table, &removed)?;
+            }
+            if !changed.is_empty() {
+                let count = changed.len();
+                result.refreshed += count - index_db.insert_file_indexes(changed)?.len();
+            }
+            thread::sleep(CHUNK_PAUSE);
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,41 @@
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use crate::api::explorer::refresh_volume_index;
+use crate::utils::volumes::{self, Volume};
+
+// 检查挂载变化的间隔
+const TICK: Duration = Duration::from_secs(10);
+
+/// 监听可移动卷：挂载时加入搜索并增量刷新卷索引，卸载后从搜索中隐藏，索引保留到下次挂载
+pub struct VolumeMonitor;
+
+impl VolumeMonitor {
+    pub fn start(app_handle: AppHandle) {
+        thread::spawn(move || {
+            // 等待刷新的卷，已有索引任务运行时下次再试
+            let mut pending: Vec<Volume> = Vec::new();
+            loop {
+                let current = volumes::removable_volumes();
+                let previous = volumes::mounted();
+                for volume in current.iter().filter(|volume| !previous.contains(volume)) {
+                    println!("卷已挂载:{:?}", volume);
+                    pending.push(volume.clone());
+                }
+                for volume in previous.iter().filter(|volume| !current.contains(volume)) {
+                    println!("卷已卸载:{:?}", volume);
+                }
+                pending.retain(|volume| current.contains(volume));
+                volumes::set_mounted(current);
+                pending.retain(|volume| match refresh_volume_index(app_handle.clone(), volume) {
+                    Ok(job_id) => {
+                        println!("刷新卷索引:{:?} {:?}", volume.path, job_id);
+                        false
+                    }
+                    Err(_) => true,
+                });
+                thread::sleep(TICK);
+            }
+        });
+    }
+}
@@ -0,0 +1,274 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use notify::event::{CreateKind, ModifyKind, RenameMode};
+use notify::{Config as WatcherConfig, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::api::content::ContentIndexer;
+use crate::api::explorer::{app_dirs, build_file_index, file_index_roots, refresh_app_index, PathFilter};
+use crate::utils::database::IndexSQL;
+use crate::utils::string_factory;
+#[cfg(target_os = "linux")]
+use crate::utils::desktop_entry;
+
+// 应用目录最后一次变化后等待 APP_REFRESH_DELAY 再刷新，安装、卸载过程中的连续变化只刷新一次
+const APP_REFRESH_DELAY: Duration = Duration::from_secs(3);
+// 重命名的 From 事件等待配对 To 事件的最长时间，超时按移出监听范围处理
+const RENAME_PAIR_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 文件索引监听，文件新增、重命名、删除时同步更新 file_index。
+/// 逐个目录非递归监听，被排除的目录（node_modules、.git 等）不占用监听数，新增的目录随之加入监听
+pub struct FileIndexWatcher {
+    index_db: IndexSQL,
+    filter: PathFilter,
+    content: Option<ContentIndexer>,
+    watcher: RecommendedWatcher,
+    // 等待配对的重命名来源：(tracker, 旧路径, 收到的时间)
+    rename_from: Option<(usize, PathBuf, Instant)>,
+}
+
+impl FileIndexWatcher {
+    pub fn start() {
+        thread::spawn(|| {
+            let roots = file_index_roots();
+            if roots.is_empty() {
+                return;
+            }
+            let (tx, rx) = channel();
+            let watcher = match RecommendedWatcher::new(tx, WatcherConfig::default()) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    println!("文件监听启动失败:{:?}", e);
+                    return;
+                }
+            };
+            let mut file_watcher = FileIndexWatcher {
+                index_db: IndexSQL::new(),
+                filter: PathFilter::from_config(),
+                content: ContentIndexer::from_config(),
+                watcher,
+                rename_from: None,
+            };
+            for root in &roots {
+                println!("开始监听文件夹:{:?}", root.path);
+                file_watcher.watch_tree(Path::new(&root.path));
+            }
+            loop {
+                match rx.recv_timeout(RENAME_PAIR_TIMEOUT) {
+                    Ok(Ok(event)) => file_watcher.handle_event(event),
+                    Ok(Err(e)) => println!("文件监听错误:{:?}", e),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if file_watcher.rename_from.as_ref().is_some_and(|(_, _, time)| time.elapsed() >= RENAME_PAIR_TIMEOUT) {
+                    file_watcher.flush_rename_from();
+                }
+            }
+        });
+    }
+
+    // 监听目录及其中未被排除的子目录
+    fn watch_tree(&mut self, dir: &Path) {
+        if !dir.is_dir() || self.filter.is_skip_path(dir) {
+            return;
+        }
+        let dirs: Vec<PathBuf> = self.filter.walk(dir)
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_dir()))
+            .map(|entry| entry.into_path())
+            .collect();
+        for dir in dirs {
+            if let Err(e) = self.watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                println!("监听文件夹失败:{:?} {:?}", dir, e);
+            }
+        }
+    }
+
+    // 未配对的 From 视为移出监听范围，按删除处理
+    fn flush_rename_from(&mut self) {
+        if let Some((_, from, _)) = self.rename_from.take() {
+            Self::on_remove(&mut self.index_db, &from);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        // 忽略文件变化后重新读取规则，已入库的路径在下次全量扫描时更新
+        for path in &event.paths {
+            self.filter.ignore_files.invalidate(path);
+        }
+        match event.kind {
+            // Linux 上同一次重命名依次报告 From、To、Both，以 tracker 配对：From 暂存，配对的 To 跳过，由 Both 原地更新路径，id 不变
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) if event.tracker().is_some() => {
+                self.flush_rename_from();
+                if let Some(from) = event.paths.first() {
+                    self.rename_from = Some((event.tracker().unwrap(), from.clone(), Instant::now()));
+                }
+                return;
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To))
+                if event.tracker().is_some() && self.rename_from.as_ref().map(|(tracker, _, _)| *tracker) == event.tracker() => {
+                return;
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                self.rename_from = None;
+                if let [from, to] = &event.paths[..] {
+                    Self::on_rename(&mut self.index_db, &self.filter, from, to);
+                    // 移动后的目录重新监听，使其中的事件报告新路径
+                    self.watch_tree(to);
+                }
+            }
+            // 未配对的 From、To 按删除、新增处理
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                self.flush_rename_from();
+                for path in &event.paths {
+                    Self::on_create(&mut self.index_db, &self.filter, path);
+                    self.watch_tree(path);
+                }
+            }
+            EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in &event.paths {
+                    Self::on_remove(&mut self.index_db, path);
+                }
+            }
+            EventKind::Modify(ModifyKind::Data(_)) | EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Modify(ModifyKind::Any) => {
+                for path in &event.paths {
+                    Self::on_modify(&mut self.index_db, &self.filter, path);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                // 未区分重命名方向的平台（如 macOS），按路径是否存在判断新增或删除
+                for path in &event.paths {
+                    if path.exists() {
+                        Self::on_create(&mut self.index_db, &self.filter, path);
+                        self.watch_tree(path);
+                    } else {
+                        Self::on_remove(&mut self.index_db, path);
+                    }
+                }
+            }
+            _ => {}
+        }
+        // 新增、移入或内容变化的文件同步提取内容，按路径逐个处理；文件夹和只有属性变化的文件不处理
+        if let (Some(indexer), EventKind::Create(CreateKind::File | CreateKind::Any)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Any)) = (&self.content, &event.kind) {
+            for path in &event.paths {
+                indexer.index_file(&mut self.index_db, path);
+            }
+        }
+    }
+
+    fn on_create(index_db: &mut IndexSQL, filter: &PathFilter, path: &Path) {
+        if !path.exists() || filter.is_skip_path(path) {
+            return;
+        }
+        // 移入的文件夹需要把子路径一并入库
+        let files = filter.walk(path)
+            .map(|entry| build_file_index(entry.path()))
+            .collect::<Vec<_>>();
+        if let Err(e) = index_db.insert_file_indexes(files) {
+            println!("新增索引失败:{:?} {:?}", path, e);
+        }
+    }
+
+    // 内容或属性变化时只更新该路径自身的大小和时间
+    fn on_modify(index_db: &mut IndexSQL, filter: &PathFilter, path: &Path) {
+        if !path.exists() || filter.is_skip_path(path) {
+            return;
+        }
+        if let Err(e) = index_db.insert_file_indexes(vec![build_file_index(path)]) {
+            println!("更新索引失败:{:?} {:?}", path, e);
+        }
+    }
+
+    fn on_remove(index_db: &mut IndexSQL, path: &Path) {
+        if let Err(e) = index_db.delete_by_path("file", &path.display().to_string()) {
+            println!("删除索引失败:{:?} {:?}", path, e);
+        }
+    }
+
+    fn on_rename(index_db: &mut IndexSQL, filter: &PathFilter, from: &Path, to: &Path) {
+        if filter.is_skip_path(to) {
+            Self::on_remove(index_db, from);
+            return;
+        }
+        let target = build_file_index(to);
+        match index_db.rename_path("file", &from.display().to_string(), &target) {
+            Ok(0) => {
+                // 旧路径未入库（被排除或已按 From/To 事件处理），新路径未入库时补充入库
+                let md5 = string_factory::md5(&target.path);
+                if !index_db.md5_is_exist("file", &md5).unwrap_or(false) {
+                    Self::on_create(index_db, filter, to);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => println!("重命名索引失败:{:?} -> {:?} {:?}", from, to, e),
+        }
+    }
+}
+
+/// 应用索引监听，启动时刷新一次应用索引，之后应用目录有变化（安装、卸载、更新）时再次刷新
+pub struct AppIndexWatcher;
+
+impl AppIndexWatcher {
+    pub fn start() {
+        thread::spawn(|| {
+            refresh_app_index();
+            let (tx, rx) = channel();
+            let mut watcher = match RecommendedWatcher::new(tx, WatcherConfig::default()) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    println!("应用目录监听启动失败:{:?}", e);
+                    return;
+                }
+            };
+            for dir in app_dirs().iter().filter(|dir| dir.is_dir()) {
+                println!("开始监听应用目录:{:?}", dir);
+                if let Err(e) = watcher.watch(dir, Self::watch_mode(dir)) {
+                    println!("监听应用目录失败:{:?} {:?}", dir, e);
+                }
+            }
+            loop {
+                match rx.recv() {
+                    Ok(Ok(event)) if Self::is_change(&event) => {}
+                    Ok(Ok(_)) => continue,
+                    Ok(Err(e)) => {
+                        println!("应用目录监听错误:{:?}", e);
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+                loop {
+                    match rx.recv_timeout(APP_REFRESH_DELAY) {
+                        Ok(_) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                refresh_app_index();
+            }
+        });
+    }
+
+    // 只有查找应用时会进入子目录的目录才递归监听：macOS 的 .app 是目录，只监听应用目录本身；
+    // Linux 的 AppImage 目录只查找第一层，递归监听会为其中无关的子目录占用 inotify 监听数
+    fn watch_mode(dir: &Path) -> RecursiveMode {
+        #[cfg(target_os = "macos")]{
+            let _ = dir;
+            RecursiveMode::NonRecursive
+        }
+        #[cfg(target_os = "linux")]{
+            if desktop_entry::application_dirs().iter().any(|app_dir| app_dir == dir) {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            }
+        }
+        #[cfg(target_os = "windows")]{
+            let _ = dir;
+            RecursiveMode::Recursive
+        }
+    }
+
+    fn is_change(event: &Event) -> bool {
+        matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_))
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::io::Write;
+use std::sync::Mutex;
+use std::time::SystemTime;
 use serde_json::{to_string, Value};
 use crate::utils::dirs::config_path;
 use anyhow::Result;
@@ -7,6 +9,15 @@ use applications::prelude::f;
 use serde::{Deserialize, Serialize};
 use walkdir::DirEntry;
 use crate::utils::database::Record;
+use crate::utils::shuangpin::PinyinInputMode;
+
+#[cfg(target_os = "linux")]
+const DEFAULT_APPIMAGE_PATHS: [&str; 3] = ["~/Applications", "~/AppImages", "~/.local/bin"];
+#[cfg(not(target_os = "linux"))]
+const DEFAULT_APPIMAGE_PATHS: [&str; 0] = [];
+
+// 检索时使用的配置缓存，按配置文件的修改时间判断是否需要重新读取
+static CACHED_CONFIG: Mutex<Option<(SystemTime, ConfigData)>> = Mutex::new(None);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BaseConfig {
@@ -24,6 +35,28 @@ pub struct BaseConfig {
     clipboard_record_file_time: Option<i32>,
     pub local_file_search_exclude_paths: Vec<String>,
     pub local_file_search_exclude_types: Vec<String>,
+    // 始终索引的路径，优先于排除规则；排除规则同样支持 ! 开头重新包含
+    #[serde(default)]
+    pub local_file_search_include_paths: Vec<String>,
+    // 拼音输入方式：全拼、微软双拼、小鹤双拼
+    #[serde(default)]
+    pub pinyin_input_mode: PinyinInputMode,
+    // 文件内容索引，默认关闭；超过大小上限（字节）的文件不读取内容
+    #[serde(default)]
+    local_file_content_index_switch: bool,
+    #[serde(default)]
+    local_file_content_index_max_size: Option<u64>,
+    // PDF、Office 文档的大小上限（字节）
+    #[serde(default)]
+    local_file_content_index_document_max_size: Option<u64>,
+    // 扫描时遵循 .gitignore、.ignore，默认开启；按索引根目录单独设置的开关优先，.larkignore 始终生效
+    #[serde(default)]
+    local_file_search_ignore_files_switch: Option<bool>,
+    #[serde(default)]
+    local_file_search_ignore_files_roots: HashMap<String, bool>,
+    // 查找 AppImage 的目录，只在 Linux 上使用；旧配置中没有该项时使用默认目录
+    #[serde(default)]
+    app_search_appimage_paths: Option<Vec<String>>,
 }
 impl Default for BaseConfig {
     #[cfg(target_os = "macos")]
@@ -68,6 +101,14 @@ impl Default for BaseConfig {
                 "kernel".to_string(),
                 "xpc".to_string(),
             ],
+            pinyin_input_mode: PinyinInputMode::Full,
+            local_file_content_index_switch: false,
+            local_file_content_index_max_size: Some(1024 * 1024),
+            local_file_content_index_document_max_size: Some(20 * 1024 * 1024),
+            local_file_search_ignore_files_switch: Some(true),
+            local_file_search_ignore_files_roots: HashMap::new(),
+            local_file_search_include_paths: vec![],
+            app_search_appimage_paths: Some(vec![]),
         }
     }
     #[cfg(target_os = "windows")]
@@ -111,6 +152,68 @@ impl Default for BaseConfig {
                 "msi".to_string(),
                 "evt".to_string(),
             ],
+            pinyin_input_mode: PinyinInputMode::Full,
+            local_file_content_index_switch: false,
+            local_file_content_index_max_size: Some(1024 * 1024),
+            local_file_content_index_document_max_size: Some(20 * 1024 * 1024),
+            local_file_search_ignore_files_switch: Some(true),
+            local_file_search_ignore_files_roots: HashMap::new(),
+            local_file_search_include_paths: vec![],
+            app_search_appimage_paths: Some(vec![]),
+        }
+    }
+    #[cfg(target_os = "linux")]
+    fn default() -> Self {
+        Self {
+            app_name: "lark".to_string(),
+            version: "1.0.0".to_string(),
+            hotkey_awaken: "Alt+Space".to_string(),
+            hotkey_clipboard: "Shift+Alt+V".to_string(),
+            clipboard_record_count_switch: false,
+            clipboard_record_count: Some(100),
+            clipboard_record_text_switch: false,
+            clipboard_record_text_time: Some(10),
+            clipboard_record_image_switch: false,
+            clipboard_record_image_time: Some(5),
+            clipboard_record_file_switch: false,
+            clipboard_record_file_time: Some(1),
+            local_file_search_exclude_paths: vec![
+                "/proc".to_string(),
+                "/sys".to_string(),
+                "/dev".to_string(),
+                "/run".to_string(),
+                "/tmp".to_string(),
+                "/var".to_string(),
+                "/boot".to_string(),
+                "/snap".to_string(),
+                "/lost+found".to_string(),
+                "~/.cache".to_string(),
+                "~/.local/share/Trash".to_string(),
+                "~/snap".to_string(),
+                "*/node_modules".to_string(),
+                "*/src-tauri/target".to_string(),
+                "*/venv".to_string(),
+                "*/dist".to_string(),
+            ],
+            local_file_search_exclude_types: vec![
+                "so".to_string(),
+                "o".to_string(),
+                "a".to_string(),
+                "ko".to_string(),
+                "pyc".to_string(),
+                "swp".to_string(),
+                "tmp".to_string(),
+                "lock".to_string(),
+                "log".to_string(),
+            ],
+            pinyin_input_mode: PinyinInputMode::Full,
+            local_file_content_index_switch: false,
+            local_file_content_index_max_size: Some(1024 * 1024),
+            local_file_content_index_document_max_size: Some(20 * 1024 * 1024),
+            local_file_search_ignore_files_switch: Some(true),
+            local_file_search_ignore_files_roots: HashMap::new(),
+            local_file_search_include_paths: vec![],
+            app_search_appimage_paths: Some(DEFAULT_APPIMAGE_PATHS.iter().map(|path| path.to_string()).collect()),
         }
     }
 }
@@ -130,12 +233,43 @@ enum ConfigUpdate {
     ClipboardRecordFileTime(Option<i32>),
     LocalFileSearchExcludePaths(Vec<String>),
     LocalFileSearchExcludeTypes(Vec<String>),
+    LocalFileSearchIncludePaths(Vec<String>),
+    PinyinInputMode(PinyinInputMode),
+    LocalFileContentIndexSwitch(bool),
+    LocalFileContentIndexMaxSize(Option<u64>),
+    LocalFileContentIndexDocumentMaxSize(Option<u64>),
+    LocalFileSearchIgnoreFilesSwitch(Option<bool>),
+    LocalFileSearchIgnoreFilesRoots(HashMap<String, bool>),
+    IndexRoots(Vec<IndexRoot>),
+    AppSearchAppimagePaths(Option<Vec<String>>),
+}
+
+/// 文件索引根目录及其扫描设置
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct IndexRoot {
+    pub path: String,
+    // 最大遍历深度，None 不限制
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+    // 追加在全局排除规则之后，仅作用于该目录
+    pub exclude_paths: Vec<String>,
+    pub exclude_types: Vec<String>,
+    // 定时重新扫描的间隔（分钟），None 不定时扫描
+    pub rescan_interval: Option<u64>,
+    // 排序权重，加在同等匹配程度的相关度得分上，权重高的根目录中的结果靠前
+    pub weight: i64,
+    // 是否遵循 .gitignore、.ignore，None 使用全局开关
+    pub ignore_files: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct ConfigData {
     pub base: BaseConfig,
     plugins: HashMap<String, Value>,
+    // 为空时使用默认根目录：macOS 为主目录，Windows 为所有本地磁盘，Linux 为主目录和挂载的磁盘
+    #[serde(default)]
+    pub index_roots: Vec<IndexRoot>,
 }
 
 
@@ -145,25 +279,95 @@ pub struct Config {
 }
 
 
+// ~/ 开头的路径替换为用户主目录，! 开头的包含规则同样处理
+fn expand_home(path: &str) -> String {
+    let (prefix, rest) = match path.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", path),
+    };
+    match rest.strip_prefix("~/") {
+        Some(rest) => {
+            let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
+            format!("{}{}/{}", prefix, home_dir, rest)
+        }
+        None => path.to_string(),
+    }
+}
+
 impl Config {
     pub fn new() -> Self {
         Self {
             config: Self::read_local_config().unwrap()
         }
     }
+    /// 每次输入都会执行的检索使用缓存的配置，配置文件未修改时不重新读取和解析
+    pub fn cached() -> Self {
+        let modified = config_path().ok()
+            .and_then(|path| path.metadata().ok())
+            .and_then(|meta| meta.modified().ok());
+        let mut cached = CACHED_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        if let (Some(modified), Some((time, config))) = (modified, cached.as_ref()) {
+            if modified == *time {
+                return Self { config: config.clone() };
+            }
+        }
+        let config = Self::read_local_config().unwrap();
+        if let Some(modified) = modified {
+            *cached = Some((modified, config.clone()));
+        }
+        Self { config }
+    }
     pub fn get_clipboard_record_limit(&self) -> i32 {
         self.config.base.clipboard_record_count.unwrap_or(-1)
     }
     pub fn get_file_search_exclude_paths(&self) -> Vec<String> {
-        let mut paths = vec![];
-        let home_dir = tauri::api::path::home_dir().unwrap().to_str().unwrap().to_string();
-        for mut path in self.config.base.local_file_search_exclude_paths.clone() {
-            if path.starts_with("~/") {
-                path = path.replace("~", &home_dir);
+        self.config.base.local_file_search_exclude_paths.iter().map(|path| expand_home(path)).collect()
+    }
+    pub fn get_file_search_exclude_types(&self) -> Vec<String> {
+        self.config.base.local_file_search_exclude_types.iter().map(|t| t.to_lowercase()).collect()
+    }
+    pub fn get_file_search_include_paths(&self) -> Vec<String> {
+        self.config.base.local_file_search_include_paths.iter().map(|path| expand_home(path)).collect()
+    }
+    pub fn get_pinyin_input_mode(&self) -> PinyinInputMode {
+        self.config.base.pinyin_input_mode
+    }
+    pub fn get_file_content_index_switch(&self) -> bool {
+        self.config.base.local_file_content_index_switch
+    }
+    pub fn get_file_content_index_max_size(&self) -> u64 {
+        self.config.base.local_file_content_index_max_size.unwrap_or(1024 * 1024)
+    }
+    pub fn get_file_content_index_document_max_size(&self) -> u64 {
+        self.config.base.local_file_content_index_document_max_size.unwrap_or(20 * 1024 * 1024)
+    }
+    pub fn get_file_search_ignore_files_switch(&self) -> bool {
+        self.config.base.local_file_search_ignore_files_switch.unwrap_or(true)
+    }
+    pub fn get_file_search_ignore_files_roots(&self) -> HashMap<String, bool> {
+        let mut roots: HashMap<String, bool> = self.config.base.local_file_search_ignore_files_roots.iter()
+            .map(|(root, on)| (expand_home(root), *on))
+            .collect();
+        for root in self.get_index_roots() {
+            if let Some(on) = root.ignore_files {
+                roots.insert(root.path, on);
             }
-            paths.push(path)
         }
-        paths
+        roots
+    }
+    pub fn get_index_roots(&self) -> Vec<IndexRoot> {
+        self.config.index_roots.iter().map(|root| IndexRoot {
+            path: expand_home(&root.path),
+            exclude_paths: root.exclude_paths.iter().map(|path| expand_home(path)).collect(),
+            exclude_types: root.exclude_types.iter().map(|t| t.to_lowercase()).collect(),
+            ..root.clone()
+        }).collect()
+    }
+    pub fn get_app_search_appimage_paths(&self) -> Vec<String> {
+        match &self.config.base.app_search_appimage_paths {
+            Some(paths) => paths.iter().map(|path| expand_home(path)).collect(),
+            None => DEFAULT_APPIMAGE_PATHS.iter().map(|path| expand_home(path)).collect(),
+        }
     }
     // todo 设置文件搜索排除 目录和类型
 
@@ -207,7 +411,16 @@ impl Config {
             ConfigUpdate::ClipboardRecordFileSwitch(value) => self.config.base.clipboard_record_file_switch = value,
             ConfigUpdate::ClipboardRecordFileTime(value) => self.config.base.clipboard_record_file_time = value,
             ConfigUpdate::LocalFileSearchExcludePaths(value) => self.config.base.local_file_search_exclude_paths = value,
-            ConfigUpdate::LocalFileSearchExcludeTypes(value) => self.config.base.local_file_search_exclude_types = value
+            ConfigUpdate::LocalFileSearchExcludeTypes(value) => self.config.base.local_file_search_exclude_types = value,
+            ConfigUpdate::LocalFileSearchIncludePaths(value) => self.config.base.local_file_search_include_paths = value,
+            ConfigUpdate::PinyinInputMode(value) => self.config.base.pinyin_input_mode = value,
+            ConfigUpdate::LocalFileContentIndexSwitch(value) => self.config.base.local_file_content_index_switch = value,
+            ConfigUpdate::LocalFileContentIndexMaxSize(value) => self.config.base.local_file_content_index_max_size = value,
+            ConfigUpdate::LocalFileContentIndexDocumentMaxSize(value) => self.config.base.local_file_content_index_document_max_size = value,
+            ConfigUpdate::LocalFileSearchIgnoreFilesSwitch(value) => self.config.base.local_file_search_ignore_files_switch = value,
+            ConfigUpdate::LocalFileSearchIgnoreFilesRoots(value) => self.config.base.local_file_search_ignore_files_roots = value,
+            ConfigUpdate::IndexRoots(value) => self.config.index_roots = value,
+            ConfigUpdate::AppSearchAppimagePaths(value) => self.config.base.app_search_appimage_paths = value
         }
     }
     pub fn save_local_config(&self) -> Result<()> {
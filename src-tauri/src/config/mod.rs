@@ -1,4 +1,4 @@
 mod config;
 pub mod plugins;
 
-pub use config::Config;
\ No newline at end of file
+pub use config::{Config, IndexRoot};
\ No newline at end of file
@@ -11,6 +11,13 @@ mod config;
 
 use tauri::{AppHandle, CustomMenuItem, GlobalShortcutManager, Manager, State, SystemTray, SystemTrayEvent, SystemTrayMenu, Window, WindowEvent};
 use crate::api::clipboard::ClipboardWatcher;
+use crate::api::watcher::{AppIndexWatcher, FileIndexWatcher};
+use crate::api::content::{ContentIndexer, CONTENT_SEARCH_PREFIX};
+use crate::api::recent::strip_recent_keyword;
+use crate::api::frecency::{Frecency, FILE_PROVIDER};
+use crate::api::scheduler::RescanScheduler;
+use crate::api::verifier::IndexVerifier;
+use crate::api::volume_monitor::VolumeMonitor;
 use rayon::prelude::*;
 use walkdir::DirEntry;
 use std::path::Path;
@@ -18,8 +25,9 @@ use std::thread;
 use libc::stat;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
-use crate::api::explorer::{create_app_index_to_sql, create_file_index_to_sql};
+use crate::api::explorer::{create_app_index_to_sql, create_file_index_to_sql, rebuild_file_index_to_sql};
 use crate::utils::database::{RecordSQL, IndexSQL, FileIndex};
+use crate::utils::query_factory::{parse_query, FileQuery};
 
 #[derive(Clone)]
 struct AppState {
@@ -38,19 +46,46 @@ fn search_keyword(component_name: &str, input_value: &str, offset: i32, params:
 {
     println!("执行搜索 {:?} 关键词 {:?} 参数 {:?}", component_name, input_value, params);
     let comps: Vec<HashMap<String, String>> = Vec::new();
+    // 内容搜索和最近文件的结果是文件，只在文件搜索中提供，启动器按应用打开结果
+    if component_name == "文件搜索" {
+        // 内容搜索单独走内容索引，文件名搜索不受影响
+        if let Some(content_keyword) = input_value.strip_prefix(CONTENT_SEARCH_PREFIX) {
+            let result = api::content::search_file_content(content_keyword.trim(), offset);
+            return rank_by_launches(result, input_value);
+        }
+        // 未输入时和 recent 关键词列出最近文件，保持最近使用的顺序
+        let recent_keyword = if input_value.trim().is_empty() { Some("") } else { strip_recent_keyword(input_value) };
+        if let Some(recent_keyword) = recent_keyword {
+            return api::recent::search_recent_files(recent_keyword, offset)
+                .into_iter().map(SearchResult::File).collect();
+        }
+    }
     if component_name == "" {
+        // 应用搜索在分页前按启动记录排序
         return api::explorer::search_app_index(input_value, offset)
             .into_iter().map(SearchResult::File).collect();
     } else if component_name == "文件搜索" {
-        let result = api::explorer::search_file_index(input_value, offset);
+        let mut result = api::explorer::search_file_index(input_value, offset);
         println!("文件搜索结果 {:?}", result.len());
-        let result = result.into_iter().map(SearchResult::File).collect();
-        return result;
+        Frecency::load(FILE_PROVIDER, input_value).rank_page(&mut result, offset, "file");
+        // 别名匹配的文件排在最前，别名只匹配关键词部分，同样按过滤条件筛选
+        let FileQuery { keyword, filter } = parse_query(input_value);
+        return api::alias::prepend_alias_matches("file", &keyword, &filter, offset, result)
+            .into_iter().map(SearchResult::File).collect();
+    } else if component_name == "重复文件" {
+        let result = api::duplicates::search_duplicate_files(input_value, offset);
+        return rank_by_launches(result, input_value);
     }
     return comps.into_iter().map(SearchResult::Map).collect();
 }
 
 
+// 已按相关度排列的文件类结果加上启动记录得分后排序
+fn rank_by_launches(mut result: Vec<FileIndex>, input_value: &str) -> Vec<SearchResult> {
+    Frecency::load(FILE_PROVIDER, input_value).rank_in_order(&mut result);
+    result.into_iter().map(SearchResult::File).collect()
+}
+
 #[cfg(target_os = "macos")]
 pub fn set_window_show(main_window: &Window) {
     // let main_window = state.app_handle.get_window("skylark").unwrap();
@@ -65,29 +100,39 @@ pub fn set_window_show(main_window: &Window) {
     main_window.emit("window-focus", true).expect("Failed to emit event");
 }
 
+#[cfg(target_os = "linux")]
+pub fn set_window_show(main_window: &Window) {
+    main_window.emit("window-focus", true).expect("Failed to emit event");
+}
+
+/// 返回索引任务 id，可用于查询进度或取消
 #[tauri::command]
-fn create_file_index(state: State<'_, AppState>) {
+fn create_file_index(state: State<'_, AppState>) -> Option<u64> {
     let app_handle = state.app_handle.clone();
-    create_file_index_to_sql(app_handle);
+    create_file_index_to_sql(app_handle)
 }
 
 #[tauri::command]
-fn create_app_index(state: State<'_, AppState>) {
-    let app_handle = state.app_handle.clone();
-    create_app_index_to_sql(app_handle);
+fn create_app_index() {
+    create_app_index_to_sql();
 }
 
 #[tauri::command]
-fn rebuild_index(state: State<'_, AppState>) {
+fn rebuild_index(state: State<'_, AppState>) -> Option<u64> {
     println!("rebuild index");
-    let _ = IndexSQL::new().clear_data("file");
-    create_file_index(state);
+    // 先停止正在运行的扫描；重建写入影子表，完成前搜索仍使用原有索引
+    api::index_job::cancel_index_job(None);
+    rebuild_file_index_to_sql(state.app_handle.clone())
 }
 
 fn main() {
     ClipboardWatcher::start();
-    IndexSQL::new();
+    IndexSQL::init();
     RecordSQL::new();
+    FileIndexWatcher::start();
+    AppIndexWatcher::start();
+    ContentIndexer::start();
+    IndexVerifier::start();
     let config = config::Config::read_local_config().unwrap();
     let config_ = config.clone();
 
@@ -110,6 +155,8 @@ fn main() {
             app.manage(AppState {
                 app_handle: app.handle(),
             });
+            RescanScheduler::start(app.handle());
+            VolumeMonitor::start(app.handle());
 
             utils::window::set_window_shadow(app);
 
@@ -161,6 +208,17 @@ fn main() {
             api::explorer::open_explorer,
             api::explorer::read_file_to_base64,
             api::explorer::read_icns_to_base64,
+            api::explorer::test_index_path,
+            api::index_job::cancel_index_job,
+            api::index_job::get_index_job_status,
+            api::duplicates::find_duplicate_files,
+            api::duplicates::get_duplicate_groups,
+            api::duplicates::trash_duplicate_file,
+            api::frecency::record_launch,
+            api::frecency::get_frecency_scores,
+            api::alias::add_alias,
+            api::alias::remove_alias,
+            api::alias::list_aliases,
             utils::window::set_window_show,
             api::clipboard::get_history_all,
             api::clipboard::get_history_id,
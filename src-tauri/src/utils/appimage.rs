@@ -0,0 +1,226 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use crate::utils::desktop_entry::{find_executable, locale_names, DesktopEntry, Package};
+use crate::utils::dirs::app_data_dir;
+use crate::utils::string_factory;
+
+// AppImage：ELF 运行时后附加 squashfs 镜像，镜像根目录下有应用的 .desktop 文件和图标。
+// 解包时优先用 unsquashfs 按偏移读取，不执行 AppImage 本身；没有 unsquashfs 时使用运行时自带的 --appimage-extract。
+// 解出的文件按 AppImage 路径和修改时间缓存，文件更新后重新解包
+
+// 常见的架构后缀，推断应用名称时去掉
+const ARCH_SUFFIXES: [&str; 8] = ["x86_64", "amd64", "x64", "aarch64", "arm64", "armhf", "i386", "i686"];
+
+/// AppImage 类型：1 为 ISO 9660 镜像，2 为 squashfs 镜像；不是 AppImage 返回 None
+pub fn appimage_type(path: &Path) -> Option<u8> {
+    let mut header = [0u8; 11];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    // ELF 头的填充字节中写有 AI 和类型号
+    match (&header[..4], &header[8..11]) {
+        (b"\x7fELF", [b'A', b'I', kind @ (1 | 2)]) => Some(*kind),
+        _ => None,
+    }
+}
+
+/// 按 ELF 头计算运行时的长度，即 squashfs 镜像在文件中的偏移：节头表偏移 + 节头大小 × 节头数量
+fn elf_size(header: &[u8]) -> Option<u64> {
+    if header.len() < 64 || &header[..4] != b"\x7fELF" {
+        return None;
+    }
+    let big_endian = header[5] == 2;
+    let read = |start: usize, len: usize| -> u64 {
+        let bytes = &header[start..start + len];
+        let fold = |value: u64, byte: &u8| (value << 8) | *byte as u64;
+        if big_endian { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) }
+    };
+    let (shoff, shentsize, shnum) = match header[4] {
+        1 => (read(0x20, 4), read(0x2E, 2), read(0x30, 2)),
+        2 => (read(0x28, 8), read(0x3A, 2), read(0x3C, 2)),
+        _ => return None,
+    };
+    Some(shoff + shentsize * shnum)
+}
+
+/// 由文件名推断应用名称，去掉版本号和架构后缀，如 Obsidian-1.5.3-x86_64.AppImage -> Obsidian
+fn title_from_file_name(path: &Path) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let is_version = |part: &str| part.strip_prefix(['v', 'V']).unwrap_or(part).starts_with(|c: char| c.is_ascii_digit());
+    let title: Vec<&str> = stem.split('-')
+        .take_while(|part| !is_version(part) && !ARCH_SUFFIXES.iter().any(|arch| part.eq_ignore_ascii_case(arch)))
+        .collect();
+    if title.is_empty() { stem } else { title.join(" ") }
+}
+
+// 从镜像中解出匹配的文件到 dest；unsquashfs 解到 dest，运行时固定解到工作目录下的 squashfs-root
+fn extract(path: &Path, dest: &Path, patterns: &[&str]) -> bool {
+    if let Some(unsquashfs) = find_executable("unsquashfs") {
+        let offset = match File::open(path).and_then(|mut file| {
+            let mut header = [0u8; 64];
+            file.read_exact(&mut header).map(|_| header)
+        }).ok().and_then(|header| elf_size(&header)) {
+            Some(offset) => offset,
+            None => return false,
+        };
+        return Command::new(unsquashfs)
+            .args(["-n", "-f", "-o", &offset.to_string(), "-d"]).arg(dest).arg(path).args(patterns)
+            .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null())
+            .status().is_ok_and(|status| status.success());
+    }
+    if find_executable(&path.to_string_lossy()).is_none() {
+        println!("AppImage 没有执行权限，且未安装 unsquashfs，跳过解包:{:?}", path);
+        return false;
+    }
+    let work_dir = match dest.parent() {
+        Some(dir) => dir,
+        None => return false,
+    };
+    patterns.iter().all(|pattern| {
+        Command::new(path).arg("--appimage-extract").arg(pattern).current_dir(work_dir)
+            .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null())
+            .status().is_ok_and(|status| status.success())
+    })
+}
+
+// 解包缓存目录，按路径区分，目录名带上修改时间和大小；同一 AppImage 的旧缓存一并删除
+fn cache_dir(path: &Path) -> Option<PathBuf> {
+    let meta = path.metadata().ok()?;
+    let mtime = meta.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    let key = string_factory::md5(&path.to_string_lossy());
+    let root = app_data_dir().ok()?.join("appimage");
+    let dir = root.join(format!("{}-{}-{}", key, mtime, meta.len()));
+    if !dir.exists() {
+        if let Ok(read_dir) = fs::read_dir(&root) {
+            for entry in read_dir.filter_map(Result::ok) {
+                if entry.file_name().to_string_lossy().starts_with(&format!("{}-", key)) {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+        fs::create_dir_all(&dir).ok()?;
+    }
+    Some(dir)
+}
+
+// 镜像根目录下符号链接的目标，如 .DirIcon -> app.png；指向镜像外的绝对路径不处理
+fn link_target(root: &Path, name: &str) -> Option<String> {
+    let target = fs::read_link(root.join(name)).ok()?;
+    let target = target.to_string_lossy().trim_start_matches("./").to_string();
+    if target.starts_with('/') { None } else { Some(target) }
+}
+
+// 解出的根目录下的文件，符号链接按链接目标查找，最多跟随 8 层
+fn root_file(root: &Path, name: &str) -> Option<PathBuf> {
+    let mut name = name.to_string();
+    for _ in 0..8 {
+        let path = root.join(&name);
+        if !path.is_symlink() {
+            return if path.is_file() { Some(path) } else { None };
+        }
+        name = link_target(root, &name)?;
+    }
+    None
+}
+
+/// 读取 AppImage 内嵌的桌面项和图标；无法解包时以文件名作为应用名称。
+/// 返回的桌面项 path 为 AppImage 本身，icon 为解出的图标文件路径
+pub fn read_entry(path: &Path) -> Option<DesktopEntry> {
+    let kind = appimage_type(path)?;
+    let mut entry = DesktopEntry::default();
+    entry.id = path.file_name()?.to_string_lossy().to_string();
+    entry.path = path.to_path_buf();
+    entry.name = title_from_file_name(path);
+    entry.package = Package::AppImage;
+    // 类型 1 的 ISO 镜像不支持按偏移解包
+    let cache = match cache_dir(path) {
+        Some(cache) if kind == 2 => cache,
+        _ => return Some(entry),
+    };
+    let root = cache.join("squashfs-root");
+    if !root.exists() && !extract(path, &root, &["*.desktop", ".DirIcon"]) {
+        println!("解包 AppImage 失败:{:?}", path);
+    }
+    let desktop_file = fs::read_dir(&root).into_iter().flatten().filter_map(Result::ok)
+        .map(|file| file.path())
+        .find(|file| file.extension().is_some_and(|ext| ext == "desktop"));
+    if let Some(embedded) = desktop_file.and_then(|file| fs::read_to_string(file).ok())
+        .and_then(|content| DesktopEntry::parse(&content, &locale_names())) {
+        if !embedded.name.is_empty() {
+            entry.name = embedded.name;
+        }
+        entry.generic_name = embedded.generic_name;
+        entry.comment = embedded.comment;
+        entry.keywords = embedded.keywords;
+        entry.terminal = embedded.terminal;
+        entry.icon = embedded.icon;
+    }
+    // 图标优先取桌面项 Icon 对应的 png，其次是 .DirIcon
+    let icon_name = if entry.icon.ends_with(".png") { entry.icon.clone() } else { format!("{}.png", entry.icon) };
+    let mut icon = None;
+    if !entry.icon.is_empty() {
+        icon = root_file(&root, &icon_name);
+        if icon.is_none() && extract(path, &root, &[&icon_name]) {
+            icon = root_file(&root, &icon_name);
+        }
+    }
+    if icon.is_none() {
+        icon = root_file(&root, ".DirIcon");
+        if let (None, Some(target)) = (&icon, link_target(&root, ".DirIcon")) {
+            if extract(path, &root, &[&target]) {
+                icon = root_file(&root, ".DirIcon");
+            }
+        }
+    }
+    entry.icon = icon.map(|icon| icon.to_string_lossy().to_string()).unwrap_or_default();
+    Some(entry)
+}
+
+/// 查找目录中的 AppImage，只查找目录本身，不递归子目录
+pub fn discover(dirs: &[String]) -> Vec<DesktopEntry> {
+    let mut apps = vec![];
+    for dir in dirs {
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(_) => continue,
+        };
+        for file in read_dir.filter_map(Result::ok) {
+            let path = file.path();
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(entry) = read_entry(&path) {
+                if !apps.iter().any(|app: &DesktopEntry| app.path == entry.path) {
+                    apps.push(entry);
+                }
+            }
+        }
+    }
+    apps
+}
+
+#[test]
+fn test_appimage_header() {
+    let mut header = vec![0u8; 64];
+    header[..4].copy_from_slice(b"\x7fELF");
+    header[4] = 2;
+    header[5] = 1;
+    header[8..11].copy_from_slice(b"AI\x02");
+    // e_shoff = 0x2f000, e_shentsize = 64, e_shnum = 30
+    header[0x28..0x30].copy_from_slice(&0x2f000u64.to_le_bytes());
+    header[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+    header[0x3C..0x3E].copy_from_slice(&30u16.to_le_bytes());
+    assert_eq!(elf_size(&header), Some(0x2f000 + 64 * 30));
+
+    let path = std::env::temp_dir().join("lark-test.AppImage");
+    fs::write(&path, &header).unwrap();
+    assert_eq!(appimage_type(&path), Some(2));
+    fs::write(&path, b"#!/bin/sh\necho").unwrap();
+    assert_eq!(appimage_type(&path), None);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(title_from_file_name(Path::new("/opt/Obsidian-1.5.3.AppImage")), "Obsidian");
+    assert_eq!(title_from_file_name(Path::new("/opt/balenaEtcher-1.18.11-x64.AppImage")), "balenaEtcher");
+    assert_eq!(title_from_file_name(Path::new("/opt/Visual-Studio-Code-x86_64.AppImage")), "Visual Studio Code");
+    assert_eq!(title_from_file_name(Path::new("/opt/krita-v5.2.2.appimage")), "krita");
+}
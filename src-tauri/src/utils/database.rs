@@ -1,14 +1,32 @@
 use std::fmt::format;
 use crate::utils::dirs::app_data_dir;
-use crate::utils::string_factory;
+use crate::utils::{fuzzy_factory, string_factory};
+use crate::utils::query_factory::{FileFilter, FileKind, PathScope};
+use crate::utils::volumes::{self, Volume};
 use anyhow::Result;
-use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use rusqlite::types::Value;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+use std::time::Duration;
 use pinyin::ToPinyin;
 
 const RECORD_SQLITE_FILE: &str = "record_data_v1.sqlite";
 const APP_FILE_INDEX_FILE: &str = "index_data_v1.sqlite";
+// 等待其他连接释放写锁的最长时间
+const BUSY_TIMEOUT_SECS: u64 = 5;
+// 卷索引的 id 从 卷编号 << VOLUME_ID_SHIFT 开始
+const VOLUME_ID_SHIFT: u32 = 40;
+/// 重建索引影子表的表名前缀，后接任务 id
+pub const SHADOW_INDEX_PREFIX: &str = "file_index_rebuild_";
+// 已存在的路径只在标题、拼音或文件属性变化时更新，保留原有 id；旧版本缺少的字段在重新扫描时补齐
+const FILE_INDEX_UPSERT: &str = r#"
+    ON CONFLICT(path) DO UPDATE SET title = excluded.title, pinyin = excluded.pinyin, abb = excluded.abb, type = excluded.type,
+        size = excluded.size, mtime = excluded.mtime, ctime = excluded.ctime, is_dir = excluded.is_dir
+    WHERE (title, pinyin, abb, type, size, mtime, ctime, is_dir)
+        IS NOT (excluded.title, excluded.pinyin, excluded.abb, excluded.type, excluded.size, excluded.mtime, excluded.ctime, excluded.is_dir)
+"#;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
 pub struct Record {
@@ -47,6 +65,69 @@ pub struct FileIndex {
     pub file_type: String,
     pub md5: String,
     pub create_time: u64,
+    /// 应用的通用名称和关键词及其拼音，以空格分隔，只用于检索
+    #[serde(default)]
+    pub keywords: String,
+    /// 文件大小（字节）
+    #[serde(default)]
+    pub size: u64,
+    /// 文件修改时间，秒级时间戳
+    #[serde(default)]
+    pub mtime: i64,
+    /// 文件创建时间，秒级时间戳
+    #[serde(default)]
+    pub ctime: i64,
+    #[serde(default)]
+    pub is_dir: bool,
+    #[serde(default)]
+    pub score: i64,
+    /// 标题中命中的字符区间，供前端高亮
+    #[serde(default)]
+    pub highlight: Vec<(usize, usize)>,
+    /// 内容搜索命中处的摘要
+    #[serde(default)]
+    pub snippet: String,
+    /// 摘要中命中的字符区间
+    #[serde(default)]
+    pub snippet_highlight: Vec<(usize, usize)>,
+}
+
+/// 文件内容提取结果，status 为 ok / skipped / failed，reason 记录跳过或失败的原因
+#[derive(Debug, Default, Clone)]
+pub struct FileContent {
+    pub id: u64,
+    pub size: u64,
+    pub mtime: i64,
+    pub content: String,
+    pub status: String,
+    pub reason: String,
+}
+
+/// 内容相同的一组文件，wasted 为除保留一份外其余副本占用的空间
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+pub struct DuplicateGroup {
+    pub id: u64,
+    pub hash: String,
+    pub size: u64,
+    pub wasted: u64,
+    pub files: Vec<FileIndex>,
+}
+
+/// 一次启动记录：输入的查询词和选中的结果，result_id 为结果路径，插件组件为其标题
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Launch {
+    pub query: String,
+    pub result_id: String,
+    pub time: i64,
+}
+
+/// 用户为应用或文件设置的别名，按路径关联，重建应用索引后仍然有效；title 为当前索引中的标题，条目不存在时为空
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Alias {
+    pub alias: String,
+    pub target: String,
+    pub path: String,
+    pub title: String,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
@@ -298,6 +379,8 @@ impl RecordSQL {
 
 pub struct IndexSQL {
     conn: Connection,
+    // 附加的已挂载卷索引，文件检索时与主索引合并
+    volumes: Vec<String>,
 }
 
 #[allow(unused)]
@@ -309,16 +392,103 @@ impl IndexSQL {
         if !Path::new(&data_dir).exists() {
             Self::init()
         }
+        let mut db = Self::open(&data_dir);
+        db.attach_volumes();
+        db
+    }
+
+    fn open(data_dir: &Path) -> Self {
         let c = Connection::open_with_flags(data_dir, OpenFlags::SQLITE_OPEN_READ_WRITE).unwrap();
-        IndexSQL { conn: c }
+        // 扫描写入时监听和搜索仍会访问数据库，WAL 模式下读不阻塞写，写锁被占用时等待而不是直接失败
+        c.pragma_update(None, "journal_mode", "WAL").unwrap_or_else(|e| println!("设置数据库日志模式失败:{:?}", e));
+        c.busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS)).unwrap();
+        IndexSQL { conn: c, volumes: vec![] }
+    }
+
+    // 附加已挂载且建过索引的卷，卸载的卷不附加，搜索结果中随之隐藏
+    fn attach_volumes(&mut self) {
+        let data_dir = app_data_dir().unwrap();
+        for volume in volumes::mounted() {
+            let path = data_dir.join(volumes::db_file_name(&volume.uuid));
+            if !path.exists() {
+                continue;
+            }
+            let schema = format!("volume_{}", self.volumes.len());
+            match self.conn.execute(&format!("ATTACH DATABASE ?1 AS {}", schema), [path.to_string_lossy()]) {
+                Ok(_) => self.volumes.push(schema),
+                Err(e) => println!("附加卷索引失败:{:?} {:?}", volume.path, e),
+            }
+        }
+    }
+
+    /// 卷索引数据库，不存在时创建；id 从卷编号对应的区间开始，与主索引和其他卷的 id 不重复
+    pub fn open_volume(volume_id: i64, uuid: &str) -> Self {
+        let data_dir = app_data_dir().unwrap().join(volumes::db_file_name(uuid));
+        let c = Self::init_schema(&data_dir);
+        c.execute(
+            "INSERT INTO sqlite_sequence (name, seq) SELECT 'file_index', ?1 WHERE NOT EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = 'file_index')",
+            [volume_id << VOLUME_ID_SHIFT],
+        ).unwrap();
+        drop(c);
+        Self::open(&data_dir)
+    }
+
+    /// id 属于某个卷索引的区间，而非主索引
+    pub fn is_volume_id(id: u64) -> bool {
+        id >> VOLUME_ID_SHIFT > 0
     }
 
     pub fn init() {
-        // 创建数据库文件并连接及创建数据库
         let data_dir = app_data_dir().unwrap().join(APP_FILE_INDEX_FILE);
-        if !Path::new(&data_dir).exists() {
-            println!("创建数据库文件:{:?}", &data_dir);
-            File::create(&data_dir).unwrap();
+        let c = Self::init_schema(&data_dir);
+        c.execute_batch(r#"
+        CREATE TABLE IF NOT EXISTS volume_index
+        (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            uuid        TEXT NOT NULL UNIQUE,
+            label       TEXT DEFAULT '',
+            path        TEXT DEFAULT '',
+            update_time INTEGER DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS duplicate_file
+        (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            group_id    INTEGER NOT NULL,
+            hash        TEXT NOT NULL,
+            size        INTEGER DEFAULT 0,
+            file_id     INTEGER NOT NULL,
+            title       TEXT DEFAULT '',
+            path        TEXT NOT NULL UNIQUE,
+            mtime       INTEGER DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_duplicate_group ON duplicate_file (group_id);
+        CREATE TABLE IF NOT EXISTS launch_history
+        (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            query       TEXT DEFAULT '',
+            provider    TEXT NOT NULL,
+            result_id   TEXT NOT NULL,
+            time        INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_launch_provider ON launch_history (provider, time);
+        CREATE TABLE IF NOT EXISTS alias
+        (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            alias       TEXT NOT NULL COLLATE NOCASE,
+            target      TEXT NOT NULL,
+            path        TEXT NOT NULL,
+            create_time INTEGER DEFAULT (strftime('%s', 'now')),
+            UNIQUE (alias, target, path)
+        );
+        CREATE INDEX IF NOT EXISTS idx_alias_path ON alias (target, path);
+        "#).unwrap();
+    }
+
+    // 创建数据库文件并连接及创建数据库，已存在时补齐新增的表结构；卷索引与主索引结构相同
+    fn init_schema(data_dir: &Path) -> Connection {
+        if !data_dir.exists() {
+            println!("创建数据库文件:{:?}", data_dir);
+            File::create(data_dir).unwrap();
         }
         let c = Connection::open_with_flags(data_dir, OpenFlags::SQLITE_OPEN_READ_WRITE).unwrap();
         let sql = r#"
@@ -333,12 +503,14 @@ impl IndexSQL {
             abb         TEXT DEFAULT '',
             type        TEXT DEFAULT 'app',
             md5         TEXT NOT NULL,
-            create_time INTEGER DEFAULT (strftime('%s', 'now'))
+            create_time INTEGER DEFAULT (strftime('%s', 'now')),
+            size        INTEGER DEFAULT 0,
+            mtime       INTEGER DEFAULT 0,
+            ctime       INTEGER DEFAULT 0,
+            is_dir      INTEGER DEFAULT 0,
+            keywords    TEXT DEFAULT ''
         );
-        CREATE INDEX IF NOT EXISTS idx_md5 ON app_index (md5);
-        "#;
-        c.execute(sql, ()).unwrap();
-        let sql = r#"
+        CREATE INDEX IF NOT EXISTS idx_app_md5 ON app_index (md5);
         CREATE TABLE IF NOT EXISTS file_index
         (
             id          INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -350,18 +522,149 @@ impl IndexSQL {
             abb         TEXT DEFAULT '',
             type        TEXT DEFAULT 'app',
             md5         TEXT NOT NULL,
-            create_time INTEGER DEFAULT (strftime('%s', 'now'))
+            create_time INTEGER DEFAULT (strftime('%s', 'now')),
+            size        INTEGER DEFAULT 0,
+            mtime       INTEGER DEFAULT 0,
+            ctime       INTEGER DEFAULT 0,
+            is_dir      INTEGER DEFAULT 0,
+            keywords    TEXT DEFAULT ''
         );
-        CREATE INDEX IF NOT EXISTS idx_md5 ON file_index (md5);
+        CREATE INDEX IF NOT EXISTS idx_file_md5 ON file_index (md5);
         "#;
-        c.execute(sql, ()).unwrap();
+        c.execute_batch(sql).unwrap();
+        for table in ["app_index", "file_index"] {
+            Self::add_columns(&c, table, &[
+                ("size", "INTEGER DEFAULT 0"),
+                ("mtime", "INTEGER DEFAULT 0"),
+                ("ctime", "INTEGER DEFAULT 0"),
+                ("is_dir", "INTEGER DEFAULT 0"),
+                ("keywords", "TEXT DEFAULT ''"),
+            ]).unwrap();
+        }
+        c.execute_batch(r#"
+        CREATE INDEX IF NOT EXISTS idx_file_mtime ON file_index (mtime);
+        CREATE INDEX IF NOT EXISTS idx_file_size ON file_index (size);
+        "#).unwrap();
+        Self::init_fts(&c, "app").unwrap();
+        Self::init_fts(&c, "file").unwrap();
+        Self::init_content(&c).unwrap();
+        Self::drop_shadow_indexes(&c).unwrap();
+        c
+    }
+
+    /// 登记挂载的卷，返回 (卷编号, 上次挂载的路径)
+    pub fn register_volume(&self, volume: &Volume) -> Result<(i64, String)> {
+        self.conn.execute(
+            "INSERT INTO volume_index (uuid, label, path) VALUES (?1, ?2, ?3) ON CONFLICT(uuid) DO NOTHING",
+            [&volume.uuid, &volume.label, &volume.path],
+        )?;
+        let (id, path): (i64, String) = self.conn.query_row(
+            "SELECT id, path FROM volume_index WHERE uuid = ?1", [&volume.uuid], |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let now = chrono::Local::now().timestamp();
+        self.conn.execute(
+            "UPDATE volume_index SET label = ?1, path = ?2, update_time = ?3 WHERE id = ?4",
+            params![volume.label, volume.path, now, id],
+        )?;
+        Ok((id, path))
+    }
+
+    // 检索的数据库：主索引，文件检索时加上已挂载卷的索引
+    fn schemas(&self, table: &str) -> Vec<&str> {
+        let mut schemas = vec!["main"];
+        if table == "file" {
+            schemas.extend(self.volumes.iter().map(String::as_str));
+        }
+        schemas
+    }
+
+    // 旧版本数据库补齐新增的列
+    fn add_columns(c: &Connection, table: &str, columns: &[(&str, &str)]) -> Result<()> {
+        let mut stmt = c.prepare(&format!("PRAGMA table_info({})", table))?;
+        let existing: Vec<String> = stmt.query_map([], |row| row.get(1))?.filter_map(|row| row.ok()).collect();
+        for (name, definition) in columns {
+            if !existing.iter().any(|column| column == name) {
+                c.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, definition), ())?;
+            }
+        }
+        Ok(())
+    }
+
+    // 创建 FTS5 全文索引表（trigram 分词以支持中文子串），并通过触发器与原表保持同步
+    fn init_fts(c: &Connection, table: &str) -> Result<()> {
+        let fts_table = format!("{}_index_fts", table);
+        let mut exists: i64 = c.query_row(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?1", [&fts_table], |row| row.get(0),
+        )?;
+        let has_keywords: i64 = c.query_row(
+            "SELECT count(*) FROM pragma_table_info(?1) WHERE name = 'keywords'", [&fts_table], |row| row.get(0),
+        )?;
+        if exists > 0 && has_keywords == 0 {
+            // 旧版本的全文索引缺少关键词列，删除后按新结构重建
+            c.execute_batch(&format!(r#"
+            DROP TRIGGER IF EXISTS {table}_index_ai;
+            DROP TRIGGER IF EXISTS {table}_index_ad;
+            DROP TABLE {table}_index_fts;
+            "#, table = table))?;
+            if table == "app" {
+                // 旧版本把关键词混在拼音列中，清空修改时间让下次刷新重新生成全部应用的条目
+                c.execute("UPDATE app_index SET mtime = 0", ())?;
+            }
+            exists = 0;
+        }
+        let sql = format!(r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS {table}_index_fts USING fts5
+        (
+            title, pinyin, abb, path, keywords,
+            content = '{table}_index', content_rowid = 'id', tokenize = 'trigram'
+        );
+        CREATE TRIGGER IF NOT EXISTS {table}_index_ai AFTER INSERT ON {table}_index BEGIN
+            INSERT INTO {table}_index_fts (rowid, title, pinyin, abb, path, keywords) VALUES (new.id, new.title, new.pinyin, new.abb, new.path, new.keywords);
+        END;
+        CREATE TRIGGER IF NOT EXISTS {table}_index_ad AFTER DELETE ON {table}_index BEGIN
+            INSERT INTO {table}_index_fts ({table}_index_fts, rowid, title, pinyin, abb, path, keywords) VALUES ('delete', old.id, old.title, old.pinyin, old.abb, old.path, old.keywords);
+        END;
+        DROP TRIGGER IF EXISTS {table}_index_au;
+        CREATE TRIGGER {table}_index_au AFTER UPDATE OF title, pinyin, abb, path, keywords ON {table}_index BEGIN
+            INSERT INTO {table}_index_fts ({table}_index_fts, rowid, title, pinyin, abb, path, keywords) VALUES ('delete', old.id, old.title, old.pinyin, old.abb, old.path, old.keywords);
+            INSERT INTO {table}_index_fts (rowid, title, pinyin, abb, path, keywords) VALUES (new.id, new.title, new.pinyin, new.abb, new.path, new.keywords);
+        END;
+        "#, table = table);
+        c.execute_batch(&sql)?;
+        if exists == 0 {
+            // 旧版本数据库已有数据，首次创建全文索引时重建
+            c.execute(&format!("INSERT INTO {0}({0}) VALUES ('rebuild')", fts_table), ())?;
+        }
+        Ok(())
+    }
+
+    // 文件内容全文索引：file_content 记录已提取文件的修改时间，file_content_fts 以 file_index 的 id 作为 rowid
+    fn init_content(c: &Connection) -> Result<()> {
+        c.execute_batch(r#"
+        CREATE TABLE IF NOT EXISTS file_content
+        (
+            id          INTEGER PRIMARY KEY,
+            mtime       INTEGER DEFAULT 0,
+            size        INTEGER DEFAULT 0
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS file_content_fts USING fts5
+        (
+            content, tokenize = 'trigram'
+        );
+        CREATE TRIGGER IF NOT EXISTS file_index_content_ad AFTER DELETE ON file_index BEGIN
+            DELETE FROM file_content WHERE id = old.id;
+            DELETE FROM file_content_fts WHERE rowid = old.id;
+        END;
+        "#)?;
+        Self::add_columns(c, "file_content", &[("status", "TEXT DEFAULT 'ok'"), ("reason", "TEXT DEFAULT ''")])?;
+        Ok(())
     }
 
     pub fn insert_file_index(&self, r: &FileIndex) -> Result<i64> {
-        let sql = "insert into file_index (title,path,desc,type,md5) values (?1,?2,?3,?4,?5)";
+        let sql = "insert into file_index (title,path,desc,pinyin,abb,type,md5,size,mtime,ctime,is_dir) values (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11)";
         let md5 = string_factory::md5(r.path.as_str());
         let res = self.conn.execute(
-            sql, [&r.title, &r.path, &r.desc, &r.file_type, &md5],
+            sql, params![r.title, r.path, r.desc, r.pinyin, r.abb, r.file_type, md5, r.size, r.mtime, r.ctime, r.is_dir],
         );
         match res {
             Ok(r) => {}
@@ -372,26 +675,99 @@ impl IndexSQL {
         Ok(self.conn.last_insert_rowid())
     }
 
-    pub fn insert_file_indexes(&mut self, paths: Vec<FileIndex>) -> Result<()> {
+    /// 返回写入失败的条目及原因，其余条目照常提交
+    pub fn insert_file_indexes(&mut self, paths: Vec<FileIndex>) -> Result<Vec<String>> {
+        self.upsert_file_indexes("file_index", paths)
+    }
+
+    fn upsert_file_indexes(&mut self, table: &str, paths: Vec<FileIndex>) -> Result<Vec<String>> {
         println!("开始提交索引:{:?}", &paths.len());
+        let mut failed = vec![];
         let tx = self.conn.transaction()?;
         {
-            let mut stmt = tx.prepare("INSERT OR IGNORE INTO file_index (title,path,desc,type,md5) VALUES (?1,?2,?3,?4,?5)")?;
+            let mut stmt = tx.prepare(&format!(
+                "INSERT INTO {} (title,path,desc,pinyin,abb,type,md5,size,mtime,ctime,is_dir) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11) {}",
+                table, FILE_INDEX_UPSERT
+            ))?;
+            // 单条写入失败时记录原因后继续，不让个别异常路径回滚整批；只有事务本身失败才返回错误
             for path in paths {
                 let md5 = string_factory::md5(path.path.as_str());
-                let res = stmt.execute(&[&path.title, &path.path, &path.desc, &path.file_type, &md5]);
-                match res {
-                    Ok(r) => {}
-                    Err(e) => { println!("插入索引失败:{:?}", e); }
+                if let Err(e) = stmt.execute(params![
+                    path.title, path.path, path.desc, path.pinyin, path.abb, path.file_type, md5, path.size, path.mtime, path.ctime, path.is_dir
+                ]) {
+                    println!("写入索引失败:{:?} {:?}", path.path, e);
+                    failed.push(format!("{} {}", path.path, e));
                 }
             }
         }
         tx.commit()?; // 提交事务
+        Ok(failed)
+    }
+
+    // 重建索引时写入的影子表，结构与 file_index 相同，不建全文索引和触发器
+    pub fn create_shadow_index(&self, name: &str) -> Result<()> {
+        self.conn.execute_batch(&format!(r#"
+        DROP TABLE IF EXISTS {0};
+        CREATE TABLE {0}
+        (
+            title       TEXT DEFAULT '',
+            path        TEXT NOT NULL UNIQUE,
+            desc        TEXT DEFAULT '',
+            pinyin      TEXT DEFAULT '',
+            abb         TEXT DEFAULT '',
+            type        TEXT DEFAULT 'app',
+            md5         TEXT NOT NULL,
+            size        INTEGER DEFAULT 0,
+            mtime       INTEGER DEFAULT 0,
+            ctime       INTEGER DEFAULT 0,
+            is_dir      INTEGER DEFAULT 0
+        );
+        "#, name))?;
+        Ok(())
+    }
+
+    pub fn insert_shadow_indexes(&mut self, name: &str, paths: Vec<FileIndex>) -> Result<Vec<String>> {
+        self.upsert_file_indexes(name, paths)
+    }
+
+    // 影子表换入 file_index：删除影子表中没有的旧条目，其余按路径合并，路径未变的条目保留 id，已提取的内容继续有效；
+    // 重建开始后由文件监听新增的条目保留。在一个事务内完成，失败时原有索引不变，返回合并后的条目数
+    pub fn swap_shadow_index(&mut self, name: &str, started_at: i64) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        tx.execute(&format!(
+            "DELETE FROM file_index WHERE create_time < ?1 AND NOT EXISTS (SELECT 1 FROM {} s WHERE s.path = file_index.path)",
+            name
+        ), [started_at])?;
+        tx.execute(&format!(
+            "INSERT INTO file_index (title,path,desc,pinyin,abb,type,md5,size,mtime,ctime,is_dir) \
+            SELECT title,path,desc,pinyin,abb,type,md5,size,mtime,ctime,is_dir FROM {} WHERE true {}",
+            name, FILE_INDEX_UPSERT
+        ), ())?;
+        tx.execute(&format!("DROP TABLE {}", name), ())?;
+        let count: usize = tx.query_row("SELECT count(*) FROM file_index", [], |row| row.get(0))?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    pub fn drop_shadow_index(&self, name: &str) -> Result<()> {
+        self.conn.execute(&format!("DROP TABLE IF EXISTS {}", name), ())?;
+        Ok(())
+    }
+
+    // 清理异常退出时遗留的影子表
+    fn drop_shadow_indexes(c: &Connection) -> Result<()> {
+        let mut stmt = c.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE ?1")?;
+        let names: Vec<String> = stmt.query_map([format!("{}%", SHADOW_INDEX_PREFIX)], |row| row.get(0))?
+            .filter_map(|row| row.ok())
+            .collect();
+        for name in names {
+            c.execute(&format!("DROP TABLE IF EXISTS {}", name), ())?;
+        }
         Ok(())
     }
 
     pub fn insert_app_index(&self, r: &FileIndex) -> Result<i64> {
-        let sql = "insert into app_index (title,path,desc,icon,pinyin,abb,md5) values (?1,?2,?3,?4,?5,?6,?7)";
+        let sql = "insert into app_index (title,path,desc,icon,pinyin,abb,type,md5) values (?1,?2,?3,?4,?5,?6,?7,?8)";
         let md5 = string_factory::md5(r.path.as_str());
         let res = self.conn.execute(
             sql, [&r.title, &r.path, &r.desc, &r.icon, &r.pinyin, &r.abb, &r.file_type, &md5],
@@ -405,15 +781,18 @@ impl IndexSQL {
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// 新增或更新应用索引，路径已存在时原地更新，保留原有 id
     pub fn insert_app_indexes(&mut self, paths: Vec<FileIndex>) -> Result<()> {
         println!("开始提交索引:{:?}", &paths.len());
         let tx = self.conn.transaction()?;
         {
-            let mut stmt = tx.prepare("INSERT OR IGNORE INTO app_index (title,path,desc,icon,pinyin,abb,md5) VALUES (?1,?2,?3,?4,?5,?6,?7)")?;
+            // type 为应用的打包方式，如 app、flatpak、snap、appimage；mtime 为应用文件的修改时间，用于判断是否需要更新
+            let mut stmt = tx.prepare(r#"INSERT INTO app_index (title,path,desc,icon,pinyin,abb,type,md5,mtime,keywords) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10)
+                ON CONFLICT(path) DO UPDATE SET title = excluded.title, desc = excluded.desc, icon = excluded.icon,
+                pinyin = excluded.pinyin, abb = excluded.abb, type = excluded.type, mtime = excluded.mtime, keywords = excluded.keywords"#)?;
             for r in paths {
                 let md5 = string_factory::md5(r.path.as_str());
-                let params = &[&r.title, &r.path, &r.desc, &r.icon, &r.pinyin, &r.abb, &md5];
-                let res = stmt.execute(params);
+                let res = stmt.execute(params![r.title, r.path, r.desc, r.icon, r.pinyin, r.abb, r.file_type, md5, r.mtime, r.keywords]);
                 match res {
                     Ok(_) => {
                         // println!("插入索引成功");
@@ -426,37 +805,28 @@ impl IndexSQL {
         Ok(())
     }
 
-    pub fn find_app(&self, keyword: &str, offset: i32) -> Result<Vec<FileIndex>> {
-        let mut sql: String = String::new();
-        sql.push_str(
-            "SELECT id, title, path, desc, icon FROM app_index where (title like ?1 or pinyin like ?2 or abb like ?2 or path like ?3)"
-        );
-        let mut limit: usize = 30;
-        let mut params: Vec<String> = vec![];
-        params.push(format!("{}%", keyword));
-        params.push(format!("{}%", keyword));
-        params.push(format!("%/Applications/{}%.app", keyword));
-        params.push(limit.to_string());
-        params.push(offset.to_string());
-        let sql = format!("{} order by create_time desc limit ?4 offset ?5", sql);
-        let mut stmt = self.conn.prepare(&sql)?;
-        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+    /// 已索引的应用，不读取图标，用于和重新查找到的应用比较
+    pub fn find_app_rows(&self) -> Result<Vec<FileIndex>> {
+        let mut stmt = self.conn.prepare("SELECT id, title, path, type, mtime FROM app_index")?;
+        let mut rows = stmt.query([])?;
         let mut res = vec![];
         while let Some(row) = rows.next()? {
-            let r = FileIndex {
+            res.push(FileIndex {
                 id: row.get(0)?,
                 title: row.get(1)?,
                 path: row.get(2)?,
-                desc: row.get(3)?,
-                icon: row.get(4)?,
-                file_type: "app".to_string(),
+                file_type: row.get(3)?,
+                mtime: row.get(4)?,
                 ..Default::default()
-            };
-            res.push(r);
+            });
         }
         Ok(res)
     }
 
+    pub fn find_app(&self, keyword: &str, offset: i32) -> Result<Vec<FileIndex>> {
+        self.search_index("app", keyword, &FileFilter::default(), &[], offset)
+    }
+
     pub fn find_app_icon(&self, app_name: &str) -> Result<FileIndex> {
         let mut sql = "SELECT id, title, icon FROM app_index where title = ?1";
         let r = self.conn.query_row(sql, [app_name], |row| {
@@ -484,17 +854,64 @@ impl IndexSQL {
         Ok(r)
     }
 
-    pub fn find_by_keyword(&self, table: &str, keyword: &str, offset: i32) -> Result<Vec<FileIndex>> {
-        let mut sql: String = String::new();
-        sql.push_str(
-            &format!("SELECT id, title, path, desc, icon, type FROM {}_index where title like ?1", table)
-        );
-        let mut limit: usize = 30;
-        let mut params: Vec<String> = vec![];
-        params.push(format!("%{}%", keyword));
-        params.push(limit.to_string());
-        params.push(offset.to_string());
-        let sql = format!("{} order by create_time desc limit ?2 offset ?3", sql);
+    /// weights 为索引根目录及其排序权重
+    pub fn find_by_keyword(&self, table: &str, keyword: &str, filter: &FileFilter, weights: &[(String, i64)], offset: i32) -> Result<Vec<FileIndex>> {
+        self.search_index(table, keyword, filter, weights, offset)
+    }
+
+    // 转义 LIKE 通配符，配合 ESCAPE '\' 使用
+    fn escape_like(keyword: &str) -> String {
+        keyword.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    }
+
+    // 按相关度检索：标题完全匹配 > 标题前缀 > 拼音/首字母前缀 > 标题包含 > 其他字段包含，同级按 bm25 排序，
+    // 所在根目录的权重加在同级的得分上。只有过滤条件没有关键词时按修改时间倒序；已挂载卷的索引与主索引合并排序
+    fn search_index(&self, table: &str, keyword: &str, filter: &FileFilter, weights: &[(String, i64)], offset: i32) -> Result<Vec<FileIndex>> {
+        let limit: i64 = 30;
+        let like = Self::escape_like(keyword);
+        let mut params: Vec<Value> = vec![];
+        params.push(Value::Text(keyword.to_string()));
+        params.push(Value::Text(format!("{}%", like)));
+        params.push(Value::Text(format!("%{}%", like)));
+        params.push(Value::Integer(limit));
+        params.push(Value::Integer(offset as i64));
+        let rank = r#"CASE WHEN f.title = ?1 COLLATE NOCASE THEN 0
+            WHEN f.title LIKE ?2 ESCAPE '\' THEN 1
+            WHEN (' ' || f.pinyin) LIKE ('% ' || ?2) ESCAPE '\' OR (' ' || f.abb) LIKE ('% ' || ?2) ESCAPE '\'
+                OR (' ' || f.keywords) LIKE ('% ' || ?2) ESCAPE '\' THEN 2
+            WHEN f.title LIKE ?3 ESCAPE '\' THEN 3
+            ELSE 4 END"#;
+        let columns = "f.id, f.title, f.path, f.desc, f.icon, f.type, f.size, f.mtime, f.ctime, f.is_dir";
+        let sql = if keyword.chars().count() >= 3 {
+            // trigram 至少需要 3 个字符，以短语形式匹配任意子串
+            params.push(Value::Text(format!("\"{}\"", keyword.replace('"', "\"\""))));
+            let filters = Self::filter_conditions(filter, &mut params);
+            let weight = Self::weight_expr(weights, &mut params);
+            let parts: Vec<String> = self.schemas(table).iter().map(|schema| format!(
+                "SELECT {columns}, {rank} AS sort_rank, bm25(t.{table}_index_fts, 10.0, 4.0, 4.0, 1.0, 4.0) AS score, {weight} AS root_weight \
+                FROM {schema}.{table}_index_fts t JOIN {schema}.{table}_index f ON f.id = t.rowid \
+                WHERE t.{table}_index_fts MATCH ?6{filters}",
+                columns = columns, rank = rank, weight = weight, schema = schema, table = table, filters = filters
+            )).collect();
+            format!("SELECT * FROM ({}) ORDER BY sort_rank, score - root_weight limit ?4 offset ?5", parts.join(" UNION ALL "))
+        } else {
+            // 多音字的多种读音以空格分隔，任意一种读音的前缀都可命中
+            let mut condition = r#"f.title LIKE ?3 ESCAPE '\' OR (' ' || f.pinyin) LIKE ('% ' || ?2) ESCAPE '\' OR (' ' || f.abb) LIKE ('% ' || ?2) ESCAPE '\'
+                OR (' ' || f.keywords) LIKE ('% ' || ?2) ESCAPE '\'"#.to_string();
+            if table == "app" {
+                // 应用路径中的英文名，如 /Applications/WeChat.app
+                params.push(Value::Text(format!("%/{}%", like)));
+                condition.push_str(r#" OR f.path LIKE ?6 ESCAPE '\'"#);
+            }
+            let filters = Self::filter_conditions(filter, &mut params);
+            let weight = Self::weight_expr(weights, &mut params);
+            let order = if keyword.is_empty() { "mtime DESC" } else { "sort_rank, length(title) - root_weight" };
+            let parts: Vec<String> = self.schemas(table).iter().map(|schema| format!(
+                "SELECT {columns}, {rank} AS sort_rank, {weight} AS root_weight FROM {schema}.{table}_index f WHERE ({condition}){filters}",
+                columns = columns, rank = rank, weight = weight, schema = schema, table = table, condition = condition, filters = filters
+            )).collect();
+            format!("SELECT * FROM ({}) ORDER BY {} limit ?4 offset ?5", parts.join(" UNION ALL "), order)
+        };
         let mut stmt = self.conn.prepare(&sql)?;
         let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
         let mut res = vec![];
@@ -506,6 +923,10 @@ impl IndexSQL {
                 desc: row.get(3)?,
                 icon: row.get(4)?,
                 file_type: row.get(5)?,
+                size: row.get(6)?,
+                mtime: row.get(7)?,
+                ctime: row.get(8)?,
+                is_dir: row.get(9)?,
                 ..Default::default()
             };
             res.push(r);
@@ -513,12 +934,586 @@ impl IndexSQL {
         Ok(res)
     }
 
+    // 路径所在根目录的排序权重，嵌套时取最近的根目录；根目录参数追加在 params 之后
+    fn weight_expr(weights: &[(String, i64)], params: &mut Vec<Value>) -> String {
+        if weights.iter().all(|(_, weight)| *weight == 0) {
+            return "0".to_string();
+        }
+        let mut roots: Vec<&(String, i64)> = weights.iter().collect();
+        roots.sort_by_key(|(root, _)| std::cmp::Reverse(root.len()));
+        let cases: Vec<String> = roots.into_iter().map(|(root, weight)| {
+            params.push(Value::Text(format!("{}{}", root.trim_end_matches(MAIN_SEPARATOR), MAIN_SEPARATOR)));
+            format!("WHEN substr(f.path, 1, length(?{0})) = ?{0} THEN {1}", params.len(), weight)
+        }).collect();
+        format!("(CASE {} ELSE 0 END)", cases.join(" "))
+    }
+
+    // 查询语法中的过滤条件转为 SQL，参数追加在 params 之后按序号引用
+    fn filter_conditions(filter: &FileFilter, params: &mut Vec<Value>) -> String {
+        fn bind(params: &mut Vec<Value>, value: Value) -> String {
+            params.push(value);
+            format!("?{}", params.len())
+        }
+        fn bind_exts<S: AsRef<str>>(params: &mut Vec<Value>, exts: &[S]) -> String {
+            let holders: Vec<String> = exts.iter().map(|ext| bind(params, Value::Text(ext.as_ref().to_string()))).collect();
+            format!("f.is_dir = 0 AND lower(f.type) IN ({})", holders.join(", "))
+        }
+        let mut conditions = vec![];
+        if !filter.exts.is_empty() {
+            conditions.push(bind_exts(params, &filter.exts));
+        }
+        for (compare, size) in &filter.size {
+            conditions.push(format!("f.size {} {}", compare.as_sql(), bind(params, Value::Integer(*size as i64))));
+        }
+        for (compare, time) in &filter.mtime {
+            conditions.push(format!("f.mtime {} {}", compare.as_sql(), bind(params, Value::Integer(*time))));
+        }
+        for (compare, time) in &filter.ctime {
+            conditions.push(format!("f.ctime {} {}", compare.as_sql(), bind(params, Value::Integer(*time))));
+        }
+        match &filter.kind {
+            Some(FileKind::Folder) => conditions.push("f.is_dir = 1".to_string()),
+            Some(FileKind::File) => conditions.push("f.is_dir = 0".to_string()),
+            Some(FileKind::Exts(exts)) => conditions.push(bind_exts(params, exts)),
+            None => {}
+        }
+        for scope in &filter.paths {
+            conditions.push(match scope {
+                PathScope::Prefix(path) => {
+                    let prefix = if path.ends_with(MAIN_SEPARATOR) { path.clone() } else { format!("{}{}", path, MAIN_SEPARATOR) };
+                    let path = bind(params, Value::Text(path.clone()));
+                    let prefix = bind(params, Value::Text(prefix));
+                    format!("(f.path = {} OR substr(f.path, 1, length({1})) = {1})", path, prefix)
+                }
+                PathScope::Segment(segment) => {
+                    // 目录名前后都是分隔符，或位于路径末尾（即该目录本身）
+                    let pattern = Self::escape_like(&format!("{}{}", MAIN_SEPARATOR, segment)).replace('*', "%").replace('?', "_");
+                    let inside = bind(params, Value::Text(format!("%{}{}%", pattern, Self::escape_like(MAIN_SEPARATOR_STR))));
+                    let itself = bind(params, Value::Text(format!("%{}", pattern)));
+                    format!(r#"(f.path LIKE {} ESCAPE '\' OR f.path LIKE {} ESCAPE '\')"#, inside, itself)
+                }
+            });
+        }
+        if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" AND {}", conditions.join(" AND "))
+        }
+    }
+
+    /// 按路径查找索引，文件检索时包括已挂载卷的索引
+    pub fn find_by_path(&self, table: &str, path: &str) -> Result<Option<FileIndex>> {
+        let parts: Vec<String> = self.schemas(table).iter().map(|schema| format!(
+            "SELECT f.id, f.title, f.path, f.desc, f.icon, f.type, f.size, f.mtime, f.ctime, f.is_dir \
+            FROM {}.{}_index f WHERE f.path = ?1",
+            schema, table
+        )).collect();
+        let sql = format!("{} limit 1", parts.join(" UNION ALL "));
+        let item = self.conn.query_row(&sql, [path], |row| {
+            Ok(FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                desc: row.get(3)?,
+                icon: row.get(4)?,
+                file_type: row.get(5)?,
+                size: row.get(6)?,
+                mtime: row.get(7)?,
+                ctime: row.get(8)?,
+                is_dir: row.get(9)?,
+                ..Default::default()
+            })
+        }).optional()?;
+        Ok(item)
+    }
+
+    pub fn insert_alias(&self, target: &str, path: &str, alias: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO alias (alias, target, path) VALUES (?1, ?2, ?3)",
+            [alias, target, path],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_alias(&self, target: &str, path: &str, alias: &str) -> Result<usize> {
+        let count = self.conn.execute(
+            "DELETE FROM alias WHERE alias = ?1 AND target = ?2 AND path = ?3",
+            [alias, target, path],
+        )?;
+        Ok(count)
+    }
+
+    /// 别名列表，target、path 为 None 时不按其过滤
+    pub fn find_aliases(&self, target: Option<&str>, path: Option<&str>) -> Result<Vec<Alias>> {
+        let mut aliases = vec![];
+        for table in ["app", "file"] {
+            if target.is_some_and(|target| target != table) {
+                continue;
+            }
+            let titles: Vec<String> = self.schemas(table).iter()
+                .map(|schema| format!("(SELECT title FROM {}.{}_index f WHERE f.path = a.path)", schema, table))
+                .collect();
+            let sql = format!(
+                "SELECT a.alias, a.target, a.path, coalesce({}, '') FROM alias a \
+                WHERE a.target = ?1 AND (?2 IS NULL OR a.path = ?2) ORDER BY a.alias",
+                titles.join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![table, path], |row| {
+                Ok(Alias { alias: row.get(0)?, target: row.get(1)?, path: row.get(2)?, title: row.get(3)? })
+            })?;
+            aliases.extend(rows.filter_map(|row| row.ok()));
+        }
+        Ok(aliases)
+    }
+
+    /// 别名以关键词开头且满足过滤条件的条目，完全相同的在前；条目已不在索引中的别名不返回
+    pub fn find_by_alias(&self, table: &str, keyword: &str, filter: &FileFilter) -> Result<Vec<FileIndex>> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut params = vec![
+            Value::Text(keyword.to_string()),
+            Value::Text(format!("{}%", Self::escape_like(keyword))),
+            Value::Text(table.to_string()),
+        ];
+        let filters = Self::filter_conditions(filter, &mut params);
+        let parts: Vec<String> = self.schemas(table).iter().map(|schema| format!(
+            "SELECT f.id, f.title, f.path, f.desc, f.icon, f.type, f.size, f.mtime, f.ctime, f.is_dir, \
+            min(CASE WHEN a.alias = ?1 THEN 0 ELSE length(a.alias) END) AS alias_rank \
+            FROM alias a JOIN {schema}.{table}_index f ON f.path = a.path \
+            WHERE a.target = ?3 AND a.alias LIKE ?2 ESCAPE '\\'{filters} GROUP BY f.id",
+            schema = schema, table = table, filters = filters
+        )).collect();
+        let sql = format!("SELECT * FROM ({}) ORDER BY alias_rank, title limit 30", parts.join(" UNION ALL "));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                desc: row.get(3)?,
+                icon: row.get(4)?,
+                file_type: row.get(5)?,
+                size: row.get(6)?,
+                mtime: row.get(7)?,
+                ctime: row.get(8)?,
+                is_dir: row.get(9)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    /// 记录一次启动，同时删除 before 之前的记录
+    pub fn insert_launch(&self, query: &str, provider: &str, result_id: &str, time: i64, before: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO launch_history (query, provider, result_id, time) VALUES (?1, ?2, ?3, ?4)",
+            params![query, provider, result_id, time],
+        )?;
+        self.conn.execute("DELETE FROM launch_history WHERE time < ?1", [before])?;
+        Ok(())
+    }
+
+    pub fn find_launches(&self, provider: &str, since: i64) -> Result<Vec<Launch>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT query, result_id, time FROM launch_history WHERE provider = ?1 AND time >= ?2 ORDER BY time DESC",
+        )?;
+        let launches = stmt.query_map(params![provider, since], |row| {
+            Ok(Launch { query: row.get(0)?, result_id: row.get(1)?, time: row.get(2)? })
+        })?.filter_map(|row| row.ok()).collect();
+        Ok(launches)
+    }
+
+    // 最近修改的文件，包括已挂载卷中的文件，修改时间晚于当前的不返回
+    pub fn find_recent_files(&self, since: i64, limit: usize) -> Result<Vec<FileIndex>> {
+        let now = chrono::Local::now().timestamp();
+        let parts: Vec<String> = self.schemas("file").iter().map(|schema| format!(
+            "SELECT f.id, f.title, f.path, f.desc, f.icon, f.type, f.size, f.mtime, f.ctime, f.is_dir \
+            FROM {}.file_index f WHERE f.is_dir = 0 AND f.mtime >= ?1 AND f.mtime <= ?2",
+            schema
+        )).collect();
+        let sql = format!("SELECT * FROM ({}) ORDER BY mtime DESC limit ?3", parts.join(" UNION ALL "));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params![since, now, limit as i64])?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                desc: row.get(3)?,
+                icon: row.get(4)?,
+                file_type: row.get(5)?,
+                size: row.get(6)?,
+                mtime: row.get(7)?,
+                ctime: row.get(8)?,
+                is_dir: row.get(9)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    // 模糊匹配候选：标题、拼音、首字母或路径按子序列命中，不读取图标
+    pub fn find_fuzzy_candidates(&self, table: &str, keyword: &str, filter: &FileFilter, limit: usize) -> Result<Vec<FileIndex>> {
+        let like = fuzzy_factory::subsequence_like_pattern(keyword);
+        let mut params = vec![Value::Text(like), Value::Integer(limit as i64)];
+        let mut condition = r#"f.title LIKE ?1 ESCAPE '\' OR f.pinyin LIKE ?1 ESCAPE '\' OR f.abb LIKE ?1 ESCAPE '\' OR f.keywords LIKE ?1 ESCAPE '\'"#.to_string();
+        if table == "app" {
+            // 应用路径中的英文名
+            condition.push_str(r#" OR f.path LIKE ?1 ESCAPE '\'"#);
+        }
+        let filters = Self::filter_conditions(filter, &mut params);
+        let parts: Vec<String> = self.schemas(table).iter().map(|schema| format!(
+            "SELECT f.id, f.title, f.path, f.desc, f.pinyin, f.abb, f.type, f.size, f.mtime, f.ctime, f.is_dir, f.keywords \
+            FROM {}.{}_index f WHERE ({}){}",
+            schema, table, condition, filters
+        )).collect();
+        let sql = format!("SELECT * FROM ({}) limit ?2", parts.join(" UNION ALL "));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            let r = FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                desc: row.get(3)?,
+                pinyin: row.get(4)?,
+                abb: row.get(5)?,
+                file_type: row.get(6)?,
+                size: row.get(7)?,
+                mtime: row.get(8)?,
+                ctime: row.get(9)?,
+                is_dir: row.get(10)?,
+                keywords: row.get(11)?,
+                ..Default::default()
+            };
+            res.push(r);
+        }
+        Ok(res)
+    }
+
+    // 待提取内容的文件：未提取过或修改时间已变化，path 不为空时只查该路径及其子路径
+    pub fn find_content_pending(&self, exts: &[&str], path: Option<&str>, limit: usize) -> Result<Vec<FileIndex>> {
+        let mut params = vec![Value::Integer(limit as i64)];
+        let holders: Vec<String> = exts.iter().map(|ext| {
+            params.push(Value::Text(ext.to_string()));
+            format!("?{}", params.len())
+        }).collect();
+        let mut condition = format!("f.is_dir = 0 AND lower(f.type) IN ({})", holders.join(", "));
+        if let Some(path) = path {
+            params.push(Value::Text(path.to_string()));
+            params.push(Value::Text(format!("{}{}", path, MAIN_SEPARATOR)));
+            condition.push_str(&format!(
+                " AND (f.path = ?{} OR substr(f.path, 1, length(?{1})) = ?{1})",
+                params.len() - 1, params.len()
+            ));
+        }
+        let sql = format!(
+            "SELECT f.id, f.path, f.size, f.mtime FROM file_index f LEFT JOIN file_content c ON c.id = f.id \
+            WHERE {} AND (c.id IS NULL OR c.mtime != f.mtime) limit ?1",
+            condition
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                size: row.get(2)?,
+                mtime: row.get(3)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    /// 单个文件未提取内容或提取后已修改时返回该文件，按路径精确查找
+    pub fn find_content_pending_file(&self, path: &str) -> Result<Option<FileIndex>> {
+        let file = self.conn.query_row(
+            "SELECT f.id, f.path, f.size, f.mtime FROM file_index f LEFT JOIN file_content c ON c.id = f.id \
+            WHERE f.path = ?1 AND f.is_dir = 0 AND (c.id IS NULL OR c.mtime != f.mtime)",
+            [path],
+            |row| Ok(FileIndex {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                size: row.get(2)?,
+                mtime: row.get(3)?,
+                ..Default::default()
+            }),
+        ).optional()?;
+        Ok(file)
+    }
+
+    // 保存提取的文件内容，跳过或失败的文件只记录修改时间和原因，文件未变化前不再重复提取
+    pub fn save_file_contents(&mut self, contents: Vec<FileContent>) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut delete = tx.prepare("DELETE FROM file_content_fts WHERE rowid = ?1")?;
+            let mut insert = tx.prepare("INSERT INTO file_content_fts (rowid, content) VALUES (?1, ?2)")?;
+            let mut state = tx.prepare("INSERT OR REPLACE INTO file_content (id, mtime, size, status, reason) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for file in contents {
+                delete.execute([file.id])?;
+                if !file.content.is_empty() {
+                    insert.execute(params![file.id, file.content])?;
+                }
+                state.execute(params![file.id, file.mtime, file.size, file.status, file.reason])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // 按内容检索，snippet 返回首个命中位置附近的原文
+    pub fn find_by_content(&self, keyword: &str, filter: &FileFilter, offset: i32) -> Result<Vec<FileIndex>> {
+        let limit: i64 = 30;
+        let mut params: Vec<Value> = vec![];
+        params.push(Value::Text(keyword.to_string()));
+        params.push(Value::Text(format!("%{}%", Self::escape_like(keyword))));
+        params.push(Value::Integer(limit));
+        params.push(Value::Integer(offset as i64));
+        let (condition, order) = if keyword.chars().count() >= 3 {
+            params.push(Value::Text(format!("\"{}\"", keyword.replace('"', "\"\""))));
+            ("file_content_fts MATCH ?5", "bm25(file_content_fts)")
+        } else {
+            // trigram 不足 3 个字符时退化为逐行 LIKE
+            (r#"file_content_fts.content LIKE ?2 ESCAPE '\'"#, "f.mtime DESC")
+        };
+        let filters = Self::filter_conditions(filter, &mut params);
+        let sql = format!(
+            "SELECT f.id, f.title, f.path, f.desc, f.type, f.size, f.mtime, f.ctime, f.is_dir, \
+            substr(file_content_fts.content, max(1, instr(lower(file_content_fts.content), lower(?1)) - 40), 160) \
+            FROM file_content_fts JOIN file_index f ON f.id = file_content_fts.rowid \
+            WHERE {}{} ORDER BY {} limit ?3 offset ?4",
+            condition, filters, order
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                desc: row.get(3)?,
+                file_type: row.get(4)?,
+                size: row.get(5)?,
+                mtime: row.get(6)?,
+                ctime: row.get(7)?,
+                is_dir: row.get(8)?,
+                snippet: row.get(9)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    // 批量读取图标
+    pub fn find_icons(&self, table: &str, ids: &[u64]) -> Result<HashMap<u64, String>> {
+        let mut icons = HashMap::new();
+        let mut stmt = self.conn.prepare(&format!("SELECT icon FROM {}_index WHERE id = ?1", table))?;
+        for id in ids {
+            // 检索后被删除的条目没有图标，跳过即可
+            if let Some(icon) = stmt.query_row([id], |row| row.get::<_, String>(0)).optional()? {
+                icons.insert(*id, icon);
+            }
+        }
+        Ok(icons)
+    }
+
     pub fn delete_by_id(&self, table: &str, id: i64) -> Result<()> {
         let sql = &format!("delete from {}_index where id = ?1", table);
         self.conn.execute(sql, [id.to_string()])?;
         Ok(())
     }
 
+    // 删除路径及其下所有子路径的索引
+    pub fn delete_by_path(&self, table: &str, path: &str) -> Result<usize> {
+        let sql = &format!("delete from {}_index where path = ?1 or substr(path, 1, length(?2)) = ?2", table);
+        let prefix = format!("{}{}", path, MAIN_SEPARATOR);
+        let count = self.conn.execute(sql, [path, prefix.as_str()])?;
+        Ok(count)
+    }
+
+    // 按 id 顺序分块读取 id 大于 after_id 的索引，用于后台校验
+    pub fn find_index_chunk(&self, table: &str, after_id: u64, limit: usize) -> Result<Vec<FileIndex>> {
+        let sql = format!("SELECT id, path, size, mtime, is_dir FROM {}_index WHERE id > ?1 ORDER BY id LIMIT ?2", table);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params![after_id, limit as i64])?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                size: row.get(2)?,
+                mtime: row.get(3)?,
+                is_dir: row.get(4)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    // 批量删除索引，包括已挂载卷中的条目，全文索引和文件内容由删除触发器清理，返回删除条数
+    pub fn delete_by_ids(&mut self, table: &str, ids: &[u64]) -> Result<usize> {
+        let schemas: Vec<String> = self.schemas(table).iter().map(|schema| schema.to_string()).collect();
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+        for schema in schemas {
+            let mut stmt = tx.prepare(&format!("DELETE FROM {}.{}_index WHERE id = ?1", schema, table))?;
+            for id in ids {
+                count += stmt.execute([id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+
+    // 重复文件的候选：大小不小于 min_size 且有相同大小的其他文件，包括已挂载卷中的文件，按大小降序
+    pub fn find_duplicate_candidates(&self, min_size: u64) -> Result<Vec<FileIndex>> {
+        let files: Vec<String> = self.schemas("file").iter().map(|schema| format!(
+            "SELECT id, title, path, size, mtime FROM {}.file_index WHERE is_dir = 0 AND size >= ?1", schema
+        )).collect();
+        let sql = format!(
+            "WITH files AS ({}) SELECT id, title, path, size, mtime FROM files \
+            WHERE size IN (SELECT size FROM files GROUP BY size HAVING count(*) > 1) ORDER BY size DESC",
+            files.join(" UNION ALL ")
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query([min_size])?;
+        let mut res = vec![];
+        while let Some(row) = rows.next()? {
+            res.push(FileIndex {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                path: row.get(2)?,
+                size: row.get(3)?,
+                mtime: row.get(4)?,
+                ..Default::default()
+            });
+        }
+        Ok(res)
+    }
+
+    // 保存查找结果，替换上次的结果
+    pub fn save_duplicate_groups(&mut self, groups: &[DuplicateGroup]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM duplicate_file", ())?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO duplicate_file (group_id, hash, size, file_id, title, path, mtime) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            )?;
+            for (index, group) in groups.iter().enumerate() {
+                for file in &group.files {
+                    stmt.execute(params![index as u64 + 1, group.hash, group.size, file.id, file.title, file.path, file.mtime])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // 按浪费空间降序分页读取重复文件组，keyword 不为空时只返回有路径包含关键词的组
+    pub fn find_duplicate_groups(&self, keyword: &str, limit: i32, offset: i32) -> Result<Vec<DuplicateGroup>> {
+        let like = format!("%{}%", Self::escape_like(keyword));
+        let mut stmt = self.conn.prepare(
+            r#"SELECT group_id, hash, size, size * (count(*) - 1) AS wasted FROM duplicate_file GROUP BY group_id
+            HAVING count(*) > 1 AND max(path LIKE ?1 ESCAPE '\') ORDER BY wasted DESC, group_id limit ?2 offset ?3"#
+        )?;
+        let mut groups: Vec<DuplicateGroup> = stmt.query_map(params![like, limit, offset], |row| {
+            Ok(DuplicateGroup {
+                id: row.get(0)?,
+                hash: row.get(1)?,
+                size: row.get(2)?,
+                wasted: row.get(3)?,
+                files: vec![],
+            })
+        })?.filter_map(|row| row.ok()).collect();
+        let mut stmt = self.conn.prepare("SELECT file_id, title, path, size, mtime FROM duplicate_file WHERE group_id = ?1 ORDER BY path")?;
+        for group in groups.iter_mut() {
+            group.files = stmt.query_map([group.id], |row| {
+                Ok(FileIndex {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    path: row.get(2)?,
+                    size: row.get(3)?,
+                    mtime: row.get(4)?,
+                    ..Default::default()
+                })
+            })?.filter_map(|row| row.ok()).collect();
+        }
+        Ok(groups)
+    }
+
+    // 与该文件同组的全部副本路径，包括自身；不在重复文件结果中时返回 None
+    pub fn find_duplicate_copies(&self, path: &str) -> Result<Option<Vec<String>>> {
+        let group_id: Option<i64> = self.conn.query_row(
+            "SELECT group_id FROM duplicate_file WHERE path = ?1", [path], |row| row.get(0),
+        ).optional()?;
+        if group_id.is_none() {
+            return Ok(None);
+        }
+        let mut stmt = self.conn.prepare("SELECT path FROM duplicate_file WHERE group_id = ?1")?;
+        let paths = stmt.query_map([group_id], |row| row.get(0))?.collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(Some(paths))
+    }
+
+    // 从重复文件组中移除一个副本，只剩一份的组一并删除，返回该副本在文件索引中的 id
+    pub fn delete_duplicate_file(&mut self, path: &str) -> Result<Option<u64>> {
+        let tx = self.conn.transaction()?;
+        let file_id = tx.query_row("SELECT file_id FROM duplicate_file WHERE path = ?1", [path], |row| row.get(0)).ok();
+        tx.execute("DELETE FROM duplicate_file WHERE path = ?1", [path])?;
+        tx.execute(
+            "DELETE FROM duplicate_file WHERE group_id IN (SELECT group_id FROM duplicate_file GROUP BY group_id HAVING count(*) < 2)", (),
+        )?;
+        tx.commit()?;
+        Ok(file_id)
+    }
+
+    // 重命名路径，子路径一并更新，返回更新条数
+    pub fn rename_path(&mut self, table: &str, from: &str, to: &FileIndex) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+        {
+            let prefix = format!("{}{}", from, MAIN_SEPARATOR);
+            let sql = format!("SELECT id, path FROM {}_index WHERE path = ?1 or substr(path, 1, length(?2)) = ?2", table);
+            let mut stmt = tx.prepare(&sql)?;
+            let rows: Vec<(i64, String)> = stmt
+                .query_map([from, prefix.as_str()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .filter_map(|row| row.ok())
+                .collect();
+            // REPLACE 冲突删除不会触发删除触发器，先显式删除目标路径上的旧记录
+            let mut delete = tx.prepare(&format!("DELETE FROM {}_index WHERE path = ?1", table))?;
+            let mut update = tx.prepare(&format!("UPDATE {}_index SET path = ?1, md5 = ?2 WHERE id = ?3", table))?;
+            for (id, path) in rows {
+                let new_path = format!("{}{}", to.path, &path[from.len()..]);
+                let md5 = string_factory::md5(&new_path);
+                delete.execute([&new_path])?;
+                update.execute(params![new_path, md5, id])?;
+                count += 1;
+            }
+            let sql = format!(
+                "UPDATE {}_index SET title = ?1, pinyin = ?2, abb = ?3, type = ?4, size = ?5, mtime = ?6, ctime = ?7, is_dir = ?8 WHERE path = ?9",
+                table
+            );
+            tx.execute(&sql, params![to.title, to.pinyin, to.abb, to.file_type, to.size, to.mtime, to.ctime, to.is_dir, to.path])?;
+            // 别名随文件一起移动
+            tx.execute(
+                "UPDATE OR REPLACE alias SET path = ?1 || substr(path, length(?2) + 1) \
+                WHERE target = ?3 AND (path = ?2 OR substr(path, 1, length(?4)) = ?4)",
+                params![to.path, from, table, prefix],
+            )?;
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+
     pub fn insert_if_not_exist(&self, table: &str, r: &FileIndex) -> Result<()> {
         let md5 = string_factory::md5(r.path.as_str());
         match self.find_by_md5(table, &md5) {
@@ -0,0 +1,473 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// XDG 桌面项（.desktop 文件）：按 XDG_DATA_HOME、XDG_DATA_DIRS 下的 applications 目录以及 Flatpak、Snap 导出目录查找应用，
+// 同一桌面项 id 以优先级高的目录为准，解析本地化名称和 Exec 字段码
+
+/// 应用的打包方式，作为 app_index 的 type 保存，界面上显示为角标
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Package {
+    #[default]
+    Native,
+    Flatpak,
+    Snap,
+    AppImage,
+}
+
+impl Package {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Package::Native => "app",
+            Package::Flatpak => "flatpak",
+            Package::Snap => "snap",
+            Package::AppImage => "appimage",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DesktopEntry {
+    /// 桌面项 id，applications 下的相对路径以 - 连接，如 org.gnome.Nautilus.desktop
+    pub id: String,
+    pub path: PathBuf,
+    pub name: String,
+    pub generic_name: String,
+    pub comment: String,
+    pub keywords: Vec<String>,
+    pub exec: String,
+    pub icon: String,
+    /// 工作目录
+    pub working_dir: String,
+    pub terminal: bool,
+    pub package: Package,
+    no_display: bool,
+    hidden: bool,
+    entry_type: String,
+    only_show_in: Vec<String>,
+    not_show_in: Vec<String>,
+    try_exec: String,
+}
+
+// 值中的转义：\s 空格、\n 换行、\t 制表符、\r 回车、\\ 反斜杠
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+// 以 ; 分隔的列表，\; 为字面的分号
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(';') => current.push(';'),
+                Some(other) => {
+                    current.push('\\');
+                    current.push(other);
+                }
+                None => current.push('\\'),
+            },
+            ';' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items.into_iter().map(|item| unescape_value(item.trim())).filter(|item| !item.is_empty()).collect()
+}
+
+/// 当前语言对应的本地化键后缀，按匹配优先级排列，如 zh_CN.UTF-8 -> [zh_CN, zh]
+pub fn locale_names() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|key| env::var(key).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    locale_variants(&locale)
+}
+
+// lang_COUNTRY.ENCODING@MODIFIER 依次匹配 lang_COUNTRY@MODIFIER、lang_COUNTRY、lang@MODIFIER、lang
+fn locale_variants(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    let locale = locale.split('.').next().unwrap_or("");
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return vec![];
+    }
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+    let mut variants = vec![];
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{}@{}", lang, modifier));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+impl DesktopEntry {
+    /// 解析 [Desktop Entry] 段，locales 为本地化键后缀的优先级
+    pub fn parse(content: &str, locales: &[String]) -> Option<DesktopEntry> {
+        let mut values: HashMap<&str, &str> = HashMap::new();
+        let mut in_desktop_entry = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_desktop_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_desktop_entry || line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                // 重复的键以第一个为准
+                values.entry(key.trim()).or_insert(value.trim());
+            }
+        }
+        if values.is_empty() {
+            return None;
+        }
+        let localized = |key: &str| -> Option<&str> {
+            locales.iter()
+                .find_map(|locale| values.get(format!("{}[{}]", key, locale).as_str()).copied())
+                .or_else(|| values.get(key).copied())
+        };
+        let string = |key: &str| localized(key).map(unescape_value).unwrap_or_default();
+        let raw = |key: &str| values.get(key).map(|value| unescape_value(value)).unwrap_or_default();
+        let boolean = |key: &str| values.get(key).is_some_and(|value| *value == "true");
+        let list = |key: &str| values.get(key).map(|value| split_list(value)).unwrap_or_default();
+        // Flatpak、snapd 导出桌面项时会写入各自的扩展键
+        let package = if values.contains_key("X-Flatpak") {
+            Package::Flatpak
+        } else if values.contains_key("X-SnapInstanceName") || values.contains_key("X-SnapAppName") {
+            Package::Snap
+        } else {
+            Package::Native
+        };
+        Some(DesktopEntry {
+            name: string("Name"),
+            generic_name: string("GenericName"),
+            comment: string("Comment"),
+            keywords: localized("Keywords").map(split_list).unwrap_or_default(),
+            exec: raw("Exec"),
+            icon: string("Icon"),
+            working_dir: raw("Path"),
+            terminal: boolean("Terminal"),
+            package,
+            no_display: boolean("NoDisplay"),
+            hidden: boolean("Hidden"),
+            entry_type: raw("Type"),
+            only_show_in: list("OnlyShowIn"),
+            not_show_in: list("NotShowIn"),
+            try_exec: raw("TryExec"),
+            ..Default::default()
+        })
+    }
+
+    pub fn load(path: &Path) -> Option<DesktopEntry> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut entry = Self::parse(&content, &locale_names())?;
+        entry.path = path.to_path_buf();
+        entry.id = path.file_name()?.to_string_lossy().to_string();
+        Some(entry)
+    }
+
+    /// 是否在应用列表中显示；desktops 为 XDG_CURRENT_DESKTOP 中的桌面环境
+    pub fn is_shown(&self, desktops: &[String]) -> bool {
+        if self.entry_type != "Application" || self.no_display || self.hidden || self.name.is_empty() || self.exec.is_empty() {
+            return false;
+        }
+        let current = |list: &[String]| list.iter().any(|desktop| desktops.iter().any(|d| d.eq_ignore_ascii_case(desktop)));
+        if !self.only_show_in.is_empty() && !current(&self.only_show_in) {
+            return false;
+        }
+        if current(&self.not_show_in) {
+            return false;
+        }
+        // TryExec 指定的程序不存在时应用未安装
+        self.try_exec.is_empty() || find_executable(&self.try_exec).is_some()
+    }
+
+    /// 按 Exec 的引号规则拆分参数并展开字段码，files 为要打开的文件或地址
+    pub fn expand_exec(&self, files: &[String]) -> Option<Vec<String>> {
+        let mut args = vec![];
+        for arg in split_exec(&self.exec)? {
+            match arg.as_str() {
+                "%f" | "%u" => args.extend(files.first().cloned()),
+                "%F" | "%U" => args.extend(files.iter().cloned()),
+                "%i" => {
+                    if !self.icon.is_empty() {
+                        args.push("--icon".to_string());
+                        args.push(self.icon.clone());
+                    }
+                }
+                _ => {
+                    let mut expanded = String::with_capacity(arg.len());
+                    let mut chars = arg.chars();
+                    while let Some(c) = chars.next() {
+                        if c != '%' {
+                            expanded.push(c);
+                            continue;
+                        }
+                        match chars.next() {
+                            Some('%') => expanded.push('%'),
+                            Some('c') => expanded.push_str(&self.name),
+                            Some('k') => expanded.push_str(&self.path.to_string_lossy()),
+                            Some('f') | Some('u') => expanded.push_str(files.first().map_or("", String::as_str)),
+                            // 已废弃的 %d %D %n %N %v %m 和未知的字段码直接移除
+                            _ => {}
+                        }
+                    }
+                    args.push(expanded);
+                }
+            }
+        }
+        if args.is_empty() { None } else { Some(args) }
+    }
+}
+
+// Exec 参数以空格分隔，双引号内的 \" \` \$ \\ 为转义字符
+fn split_exec(exec: &str) -> Option<Vec<String>> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut has_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                has_arg = true;
+            }
+            '\\' if quoted => current.push(chars.next()?),
+            ' ' | '\t' if !quoted => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    // 引号未闭合时 Exec 无效
+    if quoted {
+        return None;
+    }
+    if has_arg {
+        args.push(current);
+    }
+    Some(args)
+}
+
+/// 在 PATH 中查找可执行文件，绝对路径直接检查
+pub fn find_executable(program: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = |path: &Path| path.metadata().is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0);
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return if executable(&path) { Some(path) } else { None };
+    }
+    env::split_paths(&env::var_os("PATH")?).map(|dir| dir.join(program)).find(|path| executable(path))
+}
+
+// Flatpak 导出目录，用户安装在前；会话未把它们加入 XDG_DATA_DIRS 时同样能找到
+fn flatpak_export_dirs(data_home: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = data_home.map(|home| home.join("flatpak/exports/share")).into_iter().collect();
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs
+}
+
+const SNAP_APPLICATIONS_DIR: &str = "/var/lib/snapd/desktop/applications";
+
+fn data_home() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME").map(PathBuf::from).filter(|path| path.is_absolute())
+        .or_else(|| tauri::api::path::home_dir().map(|home| home.join(".local/share")))
+}
+
+/// 图标主题的基础目录，与应用目录顺序一致，如 ~/.local/share/icons、/var/lib/flatpak/exports/share/icons
+pub fn icon_dirs() -> Vec<PathBuf> {
+    let data_home = data_home();
+    let mut dirs: Vec<PathBuf> = data_home.iter().map(|home| home.join("icons")).collect();
+    dirs.extend(tauri::api::path::home_dir().map(|home| home.join(".icons")));
+    let data_dirs = env::var_os("XDG_DATA_DIRS").filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+    for dir in env::split_paths(&data_dirs).filter(|dir| dir.is_absolute()).chain(flatpak_export_dirs(data_home.as_deref())) {
+        let dir = dir.join("icons");
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// 应用目录，按优先级排列：XDG_DATA_HOME（默认 ~/.local/share）在前，XDG_DATA_DIRS（默认 /usr/local/share:/usr/share）在后，
+/// 最后是 Flatpak 和 Snap 的导出目录
+pub fn application_dirs() -> Vec<PathBuf> {
+    let absolute = |path: PathBuf| if path.is_absolute() { Some(path) } else { None };
+    let data_home = data_home();
+    let data_dirs = env::var_os("XDG_DATA_DIRS").filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+    let flatpak_dirs = flatpak_export_dirs(data_home.as_deref());
+    let mut dirs: Vec<PathBuf> = vec![];
+    for dir in data_home.into_iter().chain(env::split_paths(&data_dirs).filter_map(absolute)).chain(flatpak_dirs) {
+        let dir = dir.join("applications");
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    let snap_dir = PathBuf::from(SNAP_APPLICATIONS_DIR);
+    if !dirs.contains(&snap_dir) {
+        dirs.push(snap_dir);
+    }
+    dirs
+}
+
+// 按所在目录判断打包方式，用于没有写入扩展键的导出桌面项
+fn package_of_dir(dir: &Path) -> Package {
+    if dir.ends_with("flatpak/exports/share/applications") {
+        Package::Flatpak
+    } else if dir.starts_with("/var/lib/snapd/desktop") {
+        Package::Snap
+    } else {
+        Package::Native
+    }
+}
+
+// 递归收集目录下的 .desktop 文件，子目录中的桌面项 id 以 - 连接，如 kde4/konsole.desktop -> kde4-konsole.desktop
+fn collect_entries(dir: &Path, prefix: &str, entries: &mut Vec<(String, PathBuf)>) {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(_) => return,
+    };
+    for entry in read_dir.filter_map(Result::ok) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            collect_entries(&path, &format!("{}{}-", prefix, name), entries);
+        } else if name.ends_with(".desktop") {
+            entries.push((format!("{}{}", prefix, name), path));
+        }
+    }
+}
+
+/// 查找可显示的应用；同一 id 只取优先级最高的目录中的桌面项，用户目录中隐藏的桌面项会屏蔽系统目录中的同名项
+pub fn discover() -> Vec<DesktopEntry> {
+    let desktops: Vec<String> = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default()
+        .split(':').filter(|desktop| !desktop.is_empty()).map(String::from).collect();
+    let locales = locale_names();
+    let mut seen = HashSet::new();
+    let mut apps = vec![];
+    for dir in application_dirs() {
+        let dir_package = package_of_dir(&dir);
+        let mut entries = vec![];
+        collect_entries(&dir, "", &mut entries);
+        for (id, path) in entries {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            match DesktopEntry::parse(&content, &locales) {
+                Some(mut entry) if entry.is_shown(&desktops) => {
+                    if entry.package == Package::Native {
+                        entry.package = dir_package;
+                    }
+                    apps.push(DesktopEntry { id, path, ..entry })
+                }
+                _ => {}
+            }
+        }
+    }
+    apps
+}
+
+#[test]
+fn test_parse_desktop_entry() {
+    let content = r#"
+[Desktop Entry]
+Type=Application
+Name=Text Editor
+Name[zh_CN]=文本编辑器
+GenericName=Editor
+GenericName[zh]=编辑器
+Keywords=text;edit\;plain;
+Keywords[zh_CN]=文本;记事本;
+Exec=gedit --name "%c" --path="/opt/my app" %U %%s
+Icon=org.gnome.gedit
+OnlyShowIn=GNOME;Unity;
+Terminal=false
+
+[Desktop Action new-window]
+Name=New Window
+Exec=gedit --new-window
+"#;
+    let locales = locale_variants("zh_CN.UTF-8");
+    assert_eq!(locales, vec!["zh_CN", "zh"]);
+    let entry = DesktopEntry::parse(content, &locales).unwrap();
+    assert_eq!(entry.name, "文本编辑器");
+    assert_eq!(entry.generic_name, "编辑器");
+    assert_eq!(entry.keywords, vec!["文本", "记事本"]);
+    assert!(entry.is_shown(&["GNOME".to_string()]));
+    assert!(!entry.is_shown(&["KDE".to_string()]));
+    assert!(!entry.is_shown(&[]));
+    let files = vec!["/tmp/a.txt".to_string(), "/tmp/b.txt".to_string()];
+    assert_eq!(entry.expand_exec(&files).unwrap(), vec!["gedit", "--name", "文本编辑器", "--path=/opt/my app", "/tmp/a.txt", "/tmp/b.txt", "%s"]);
+    assert_eq!(entry.expand_exec(&[]).unwrap(), vec!["gedit", "--name", "文本编辑器", "--path=/opt/my app", "%s"]);
+
+    let entry = DesktopEntry::parse(content, &[]).unwrap();
+    assert_eq!(entry.name, "Text Editor");
+    assert_eq!(entry.keywords, vec!["text", "edit;plain"]);
+
+    let hidden = DesktopEntry::parse("[Desktop Entry]\nType=Application\nName=a\nExec=a\nNoDisplay=true", &[]).unwrap();
+    assert!(!hidden.is_shown(&[]));
+    // Exec 中的 \\ 先按字符串转义为 \，再按引号规则转义
+    let entry = DesktopEntry::parse(r#"[Desktop Entry]
+Exec=sh -c "echo \\"a b\\" \\$HOME" %i %f"#, &[]).unwrap();
+    assert_eq!(entry.expand_exec(&[]).unwrap(), vec!["sh", "-c", r#"echo "a b" $HOME"#]);
+    assert_eq!(split_exec(r#"a "b"#), None);
+    assert_eq!(locale_variants("sr_RS@latin"), vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]);
+
+    let flatpak = DesktopEntry::parse("[Desktop Entry]\nName=GIMP\nExec=/usr/bin/flatpak run org.gimp.GIMP @@u %U @@\nX-Flatpak=org.gimp.GIMP", &[]).unwrap();
+    assert_eq!(flatpak.package, Package::Flatpak);
+    let snap = DesktopEntry::parse("[Desktop Entry]\nName=VLC\nX-SnapInstanceName=vlc\nExec=env BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/vlc_vlc.desktop /snap/bin/vlc %U", &[]).unwrap();
+    assert_eq!(snap.package, Package::Snap);
+    assert_eq!(snap.expand_exec(&[]).unwrap(), vec!["env", "BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/vlc_vlc.desktop", "/snap/bin/vlc"]);
+    assert_eq!(package_of_dir(Path::new("/home/user/.local/share/flatpak/exports/share/applications")), Package::Flatpak);
+    assert_eq!(package_of_dir(Path::new(SNAP_APPLICATIONS_DIR)), Package::Snap);
+    assert_eq!(package_of_dir(Path::new("/usr/share/applications")), Package::Native);
+}
@@ -0,0 +1,228 @@
+use std::fs;
+use std::io::{Cursor, Read};
+use std::panic;
+use std::path::Path;
+use encoding_rs::{Encoding, GBK};
+use lopdf::Document;
+use pdf_extract::{output_doc, PlainTextOutput};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+// 文件文本提取：纯文本按编码解码，PDF 解析文本流，OOXML（docx/xlsx/pptx）和 ODF（odt/ods/odp）读取压缩包中的 XML
+
+pub const DOCUMENT_EXTS: &[&str] = &["pdf", "docx", "xlsx", "pptx", "odt", "ods", "odp"];
+// 加密的 OOXML 文档保存为 OLE 复合文件，而不是 zip
+const CFB_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+// 压缩包内单个 XML 解压后的大小上限，防止压缩炸弹
+const MAX_XML_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, PartialEq)]
+pub enum ExtractError {
+    TooLarge,
+    Encrypted,
+    Binary,
+    Failed(String),
+}
+
+impl ExtractError {
+    /// 记录到内容索引中的跳过或失败原因
+    pub fn reason(&self) -> String {
+        match self {
+            ExtractError::TooLarge => "too_large".to_string(),
+            ExtractError::Encrypted => "encrypted".to_string(),
+            ExtractError::Binary => "binary".to_string(),
+            ExtractError::Failed(e) => e.clone(),
+        }
+    }
+}
+
+fn failed<E: std::fmt::Display>(e: E) -> ExtractError {
+    ExtractError::Failed(e.to_string())
+}
+
+/// 提取文件文本，文件超过 max_size 字节时不读取
+pub fn extract_text(path: &Path, max_size: u64) -> Result<String, ExtractError> {
+    if fs::metadata(path).map_err(failed)?.len() > max_size {
+        return Err(ExtractError::TooLarge);
+    }
+    let bytes = fs::read(path).map_err(failed)?;
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "pdf" => extract_pdf(&bytes),
+        "docx" => extract_ooxml(&bytes, |name| name == "word/document.xml"),
+        "xlsx" => extract_ooxml(&bytes, |name| name == "xl/sharedStrings.xml" || name.starts_with("xl/worksheets/sheet")),
+        "pptx" => extract_ooxml(&bytes, |name| name.starts_with("ppt/slides/slide") && name.ends_with(".xml")),
+        "odt" | "ods" | "odp" => extract_odf(&bytes),
+        _ => decode_text(&bytes).ok_or(ExtractError::Binary),
+    }
+}
+
+/// 按 BOM 解码，无 BOM 时依次尝试 UTF-8、GBK；含 NUL 字节的视为二进制
+pub fn decode_text(bytes: &[u8]) -> Option<String> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Some(text.into_owned());
+    }
+    if bytes.iter().take(8192).any(|&b| b == 0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text.to_string()),
+        Err(_) => {
+            let (text, _, had_errors) = GBK.decode(bytes);
+            if had_errors { None } else { Some(text.into_owned()) }
+        }
+    }
+}
+
+fn extract_pdf(bytes: &[u8]) -> Result<String, ExtractError> {
+    // 解析异常的 PDF 可能直接 panic
+    let result = panic::catch_unwind(|| {
+        let mut doc = Document::load_mem(bytes).map_err(failed)?;
+        // 只设置了权限密码的文档可以用空密码解密
+        if doc.is_encrypted() && doc.decrypt("").is_err() {
+            return Err(ExtractError::Encrypted);
+        }
+        let mut text = String::new();
+        output_doc(&doc, &mut PlainTextOutput::new(&mut text)).map_err(failed)?;
+        Ok(text)
+    });
+    result.unwrap_or_else(|_| Err(ExtractError::Failed("pdf parse panic".to_string())))
+}
+
+fn open_zip(bytes: &[u8]) -> Result<ZipArchive<Cursor<&[u8]>>, ExtractError> {
+    if bytes.starts_with(CFB_SIGNATURE) {
+        return Err(ExtractError::Encrypted);
+    }
+    ZipArchive::new(Cursor::new(bytes)).map_err(failed)
+}
+
+fn read_zip_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Vec<u8>, ExtractError> {
+    let entry = archive.by_name(name).map_err(|e| match e {
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => ExtractError::Encrypted,
+        e => failed(e),
+    })?;
+    let mut xml = Vec::new();
+    entry.take(MAX_XML_SIZE).read_to_end(&mut xml).map_err(failed)?;
+    Ok(xml)
+}
+
+// 幻灯片、工作表按编号排序：slide2.xml 在 slide10.xml 之前
+fn part_order(name: &str) -> (usize, u64, String) {
+    let stem = name.trim_end_matches(".xml");
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let number = stem[stem.len() - digits..].parse().unwrap_or(0);
+    (stem.len() - digits, number, stem.to_string())
+}
+
+fn extract_ooxml(bytes: &[u8], is_text_part: impl Fn(&str) -> bool) -> Result<String, ExtractError> {
+    let mut archive = open_zip(bytes)?;
+    let mut parts: Vec<String> = archive.file_names().filter(|name| is_text_part(name)).map(|name| name.to_string()).collect();
+    parts.sort_by_key(|name| part_order(name));
+    let mut text = String::new();
+    for part in parts {
+        let xml = read_zip_entry(&mut archive, &part)?;
+        // w:t（Word）、a:t（PowerPoint）、t（Excel 共享字符串）
+        xml_text(&xml, &[b"t"], &[b"p", b"si", b"row"], &mut text)?;
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn extract_odf(bytes: &[u8]) -> Result<String, ExtractError> {
+    let mut archive = open_zip(bytes)?;
+    if let Ok(manifest) = read_zip_entry(&mut archive, "META-INF/manifest.xml") {
+        if String::from_utf8_lossy(&manifest).contains("encryption-data") {
+            return Err(ExtractError::Encrypted);
+        }
+    }
+    let xml = read_zip_entry(&mut archive, "content.xml")?;
+    let mut text = String::new();
+    xml_text(&xml, &[], &[b"p", b"h", b"table-row"], &mut text)?;
+    Ok(text)
+}
+
+// 读取 XML 文本节点，text_tags 为空时读取全部文本；break_tags 结束处换行
+fn xml_text(xml: &[u8], text_tags: &[&[u8]], break_tags: &[&[u8]], out: &mut String) -> Result<(), ExtractError> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut depth = 0;
+    loop {
+        match reader.read_event_into(&mut buf).map_err(failed)? {
+            Event::Start(e) => {
+                if text_tags.contains(&e.local_name().as_ref()) {
+                    depth += 1;
+                }
+            }
+            Event::End(e) => {
+                let name = e.local_name();
+                if text_tags.contains(&name.as_ref()) {
+                    depth -= 1;
+                }
+                if break_tags.contains(&name.as_ref()) {
+                    out.push('\n');
+                }
+            }
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" | b"s" => out.push(' '),
+                b"br" | b"line-break" => out.push('\n'),
+                _ => {}
+            },
+            Event::Text(e) if text_tags.is_empty() || depth > 0 => {
+                out.push_str(&e.unescape().map_err(failed)?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+#[test]
+fn test_extract_text() {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    let dir = std::env::temp_dir().join("lark_extract_test");
+    fs::create_dir_all(&dir).unwrap();
+    let write_zip = |name: &str, entries: &[(&str, &str)]| {
+        let path = dir.join(name);
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        for (entry, content) in entries {
+            zip.start_file(*entry, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    };
+    let docx = write_zip("a.docx", &[(
+        "word/document.xml",
+        r#"<w:document xmlns:w="w"><w:body><w:p><w:r><w:t>季度</w:t></w:r><w:r><w:t xml:space="preserve"> 报告 &amp; 总结</w:t></w:r></w:p><w:p><w:r><w:instrText>PAGE</w:instrText><w:t>第二段</w:t></w:r></w:p></w:body></w:document>"#,
+    )]);
+    assert_eq!(extract_text(&docx, 1 << 20).unwrap(), "季度 报告 & 总结\n第二段\n\n");
+    let pptx = write_zip("a.pptx", &[
+        ("ppt/slides/slide10.xml", r#"<p:sld xmlns:a="a" xmlns:p="p"><a:p><a:r><a:t>十</a:t></a:r></a:p></p:sld>"#),
+        ("ppt/slides/slide2.xml", r#"<p:sld xmlns:a="a" xmlns:p="p"><a:p><a:r><a:t>二</a:t></a:r></a:p></p:sld>"#),
+        ("ppt/slides/_rels/slide2.xml.rels", "<Relationships/>"),
+    ]);
+    assert_eq!(extract_text(&pptx, 1 << 20).unwrap(), "二\n\n十\n\n");
+    let odt = write_zip("a.odt", &[
+        ("content.xml", r#"<office:document-content xmlns:text="t"><office:body><text:h>标题</text:h><text:p>正文<text:s/>内容</text:p></office:body></office:document-content>"#),
+    ]);
+    assert_eq!(extract_text(&odt, 1 << 20).unwrap(), "标题\n正文 内容\n");
+    let encrypted_odt = write_zip("b.odt", &[
+        ("META-INF/manifest.xml", r#"<manifest:manifest><manifest:file-entry><manifest:encryption-data/></manifest:file-entry></manifest:manifest>"#),
+        ("content.xml", "<x/>"),
+    ]);
+    assert_eq!(extract_text(&encrypted_odt, 1 << 20), Err(ExtractError::Encrypted));
+    let encrypted_docx = dir.join("b.docx");
+    fs::write(&encrypted_docx, [CFB_SIGNATURE, &[0; 8]].concat()).unwrap();
+    assert_eq!(extract_text(&encrypted_docx, 1 << 20), Err(ExtractError::Encrypted));
+    assert_eq!(extract_text(&docx, 10), Err(ExtractError::TooLarge));
+    assert_eq!(decode_text("报告".as_bytes()), Some("报告".to_string()));
+    let (gbk, _, _) = GBK.encode("报告");
+    assert_eq!(decode_text(&gbk), Some("报告".to_string()));
+    assert_eq!(decode_text(&[0x7f, 0x45, 0x4c, 0x46, 0, 0]), None);
+}
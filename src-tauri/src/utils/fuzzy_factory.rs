@@ -0,0 +1,212 @@
+// 模糊子序列匹配，评分规则参考 fzf：连续匹配、单词边界、驼峰位置加分，间隔扣分
+
+const SCORE_MATCH: i64 = 16;
+const SCORE_GAP_START: i64 = -3;
+const SCORE_GAP_EXTENSION: i64 = -1;
+const BONUS_BOUNDARY: i64 = SCORE_MATCH / 2;
+const BONUS_NON_WORD: i64 = SCORE_MATCH / 2;
+const BONUS_CAMEL: i64 = BONUS_BOUNDARY + SCORE_GAP_EXTENSION;
+const BONUS_CONSECUTIVE: i64 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+// 超长文本只匹配前面部分，避免评分矩阵过大
+const MAX_TEXT_LEN: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// 命中的字符区间（按字符计数，左闭右开）
+    pub ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    NonWord,
+    Lower,
+    Upper,
+    Digit,
+    Letter,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_ascii_lowercase() {
+        CharClass::Lower
+    } else if c.is_ascii_uppercase() {
+        CharClass::Upper
+    } else if c.is_ascii_digit() {
+        CharClass::Digit
+    } else if c.is_alphanumeric() {
+        CharClass::Letter
+    } else {
+        CharClass::NonWord
+    }
+}
+
+fn position_bonus(prev: CharClass, current: CharClass) -> i64 {
+    match (prev, current) {
+        (CharClass::NonWord, c) if c != CharClass::NonWord => BONUS_BOUNDARY,
+        (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL,
+        (p, CharClass::Digit) if p != CharClass::Digit => BONUS_CAMEL,
+        (_, CharClass::NonWord) => BONUS_NON_WORD,
+        _ => 0,
+    }
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// 将相邻的命中位置合并为区间
+fn to_ranges(positions: &[usize]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &pos in positions {
+        match ranges.last_mut() {
+            Some(last) if last.1 == pos => last.1 = pos + 1,
+            _ => ranges.push((pos, pos + 1)),
+        }
+    }
+    ranges
+}
+
+/// 模糊匹配 pattern（忽略大小写和空白），不构成子序列时返回 None
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).map(fold_case).collect();
+    let text: Vec<char> = text.chars().take(MAX_TEXT_LEN).collect();
+    let (n, m) = (pattern.len(), text.len());
+    if n == 0 || n > m {
+        return None;
+    }
+    let folded: Vec<char> = text.iter().map(|&c| fold_case(c)).collect();
+
+    // 先确认是子序列，确定第一个字符最早、最后一个字符最晚可能的位置以缩小计算范围
+    let mut first = None;
+    let mut idx = 0;
+    for (j, &c) in folded.iter().enumerate() {
+        if c == pattern[idx] {
+            if idx == 0 {
+                first = Some(j);
+            }
+            idx += 1;
+            if idx == n {
+                break;
+            }
+        }
+    }
+    if idx < n {
+        return None;
+    }
+    let first = first.unwrap();
+    let last = folded.iter().rposition(|&c| c == pattern[n - 1]).unwrap();
+
+    let mut bonus = vec![0i64; m];
+    let mut prev_class = CharClass::NonWord;
+    for (j, &c) in text.iter().enumerate() {
+        let class = char_class(c);
+        bonus[j] = position_bonus(prev_class, class);
+        prev_class = class;
+    }
+
+    const NONE: i64 = i64::MIN / 2;
+    // score[i][j]：pattern[i] 命中 text[j] 时的最佳得分；from[i][j]：pattern[i-1] 命中的位置
+    let mut score = vec![vec![NONE; m]; n];
+    let mut consecutive = vec![vec![0usize; m]; n];
+    let mut from = vec![vec![usize::MAX; m]; n];
+
+    for j in first..=last {
+        if folded[j] == pattern[0] {
+            // 开头未命中的字符少量扣分，让靠前的匹配更优
+            let leading = (j as i64).min(15) * SCORE_GAP_EXTENSION;
+            score[0][j] = SCORE_MATCH + bonus[j] * BONUS_FIRST_CHAR_MULTIPLIER + leading;
+            consecutive[0][j] = 1;
+        }
+    }
+    for i in 1..n {
+        // gap_best：跳过至少一个字符后接到 j 的最佳前驱得分（已含间隔扣分）
+        let mut gap_best = NONE;
+        let mut gap_from = usize::MAX;
+        for j in first + i..=last {
+            if j >= 2 {
+                let candidate = score[i - 1][j - 2];
+                if gap_best > NONE {
+                    gap_best += SCORE_GAP_EXTENSION;
+                }
+                if candidate > NONE && candidate + SCORE_GAP_START > gap_best {
+                    gap_best = candidate + SCORE_GAP_START;
+                    gap_from = j - 2;
+                }
+            }
+            if folded[j] != pattern[i] {
+                continue;
+            }
+            let mut best = NONE;
+            if gap_best > NONE {
+                best = gap_best + SCORE_MATCH + bonus[j];
+                from[i][j] = gap_from;
+                consecutive[i][j] = 1;
+            }
+            let prev = score[i - 1][j - 1];
+            if prev > NONE {
+                let run_bonus = bonus[j].max(BONUS_CONSECUTIVE);
+                let candidate = prev + SCORE_MATCH + run_bonus;
+                if candidate >= best {
+                    best = candidate;
+                    from[i][j] = j - 1;
+                    consecutive[i][j] = consecutive[i - 1][j - 1] + 1;
+                }
+            }
+            score[i][j] = best;
+        }
+    }
+
+    let (mut end, mut best) = (usize::MAX, NONE);
+    for j in first..=last {
+        if score[n - 1][j] > best {
+            best = score[n - 1][j];
+            end = j;
+        }
+    }
+    if best <= NONE {
+        return None;
+    }
+    let mut positions = vec![0usize; n];
+    let mut j = end;
+    for i in (0..n).rev() {
+        positions[i] = j;
+        if i > 0 {
+            j = from[i][j];
+        }
+    }
+    Some(FuzzyMatch { score: best, ranges: to_ranges(&positions) })
+}
+
+/// 子序列 LIKE 模式，如 vsc -> %v%s%c%，用于在数据库中预筛候选
+pub fn subsequence_like_pattern(pattern: &str) -> String {
+    let mut like = String::from("%");
+    for c in pattern.chars().filter(|c| !c.is_whitespace()) {
+        if c == '%' || c == '_' || c == '\\' {
+            like.push('\\');
+        }
+        like.push(c);
+        like.push('%');
+    }
+    like
+}
+
+#[test]
+fn test_fuzzy_match() {
+    let m = fuzzy_match("vsc", "Visual Studio Code").unwrap();
+    assert_eq!(m.ranges, vec![(0, 1), (7, 8), (14, 15)]);
+    assert!(fuzzy_match("vsc", "Visual Studio").is_none());
+    // 边界匹配优于词中匹配
+    let boundary = fuzzy_match("fb", "foo_bar").unwrap();
+    let inner = fuzzy_match("fb", "fabric").unwrap();
+    assert!(boundary.score > inner.score);
+    // 连续匹配优于分散匹配
+    let consecutive = fuzzy_match("code", "Code Runner").unwrap();
+    let scattered = fuzzy_match("code", "Cloud Desktop Editor").unwrap();
+    assert!(consecutive.score > scattered.score);
+    assert_eq!(consecutive.ranges, vec![(0, 4)]);
+    // 驼峰
+    let camel = fuzzy_match("gh", "GitHubDesktop").unwrap();
+    assert_eq!(camel.ranges, vec![(0, 1), (3, 4)]);
+    assert_eq!(subsequence_like_pattern("a_b"), "%a%\\_%b%");
+}
@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+// 文件索引的排除/包含规则，语法与 .gitignore 一致：
+// 规则按顺序匹配，后面的优先；! 开头为重新包含；/ 结尾只匹配目录；不含 / 的规则匹配任意层级的文件名；
+// 绝对路径只匹配该路径；命中目录时整个子目录一同排除。
+// 与 .gitignore 相同，被排除目录下的文件不能用相对规则重新包含，需要使用绝对路径规则或 include 配置
+
+/// 规则来源，用于说明路径被哪条配置排除
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSource {
+    Hidden,
+    ExcludePaths,
+    ExcludeTypes,
+    IncludePaths,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PathRule {
+    pub source: RuleSource,
+    /// 配置中的原始写法
+    pub pattern: String,
+    pub include: bool,
+}
+
+// glob 适用的条目类型
+#[derive(Clone, Copy)]
+enum Target {
+    Any,
+    Dir,
+    File,
+}
+
+#[derive(Clone, Default)]
+pub struct PathRules {
+    rules: Vec<PathRule>,
+    set: GlobSet,
+    // glob 下标 -> (规则下标, 适用的条目类型)
+    globs: Vec<(usize, Target)>,
+    // 绝对路径包含规则的固定前缀，被排除的上级目录仍需进入
+    include_bases: Vec<PathBuf>,
+}
+
+// 隐藏文件和 $ 开头的系统目录（如 $RECYCLE.BIN），优先级最低，可被包含规则覆盖
+const BUILTIN_RULES: &[&str] = &[".*", "$*"];
+
+fn is_glob_meta(c: char) -> bool {
+    matches!(c, '*' | '?' | '[' | '{')
+}
+
+fn is_absolute(pattern: &str) -> bool {
+    let bytes = pattern.as_bytes();
+    pattern.starts_with('/') || (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'/')
+}
+
+// 转为 glob，返回 (glob, 是否只匹配目录, 绝对路径的固定前缀)
+fn to_glob(pattern: &str) -> (String, bool, Option<PathBuf>) {
+    let mut pattern = if cfg!(windows) { pattern.replace('\\', "/") } else { pattern.to_string() };
+    let dir_only = pattern.len() > 1 && pattern.ends_with('/');
+    if dir_only {
+        pattern.pop();
+    }
+    // 兼容旧写法 */node_modules、*/src-tauri/target：任意层级下的该路径
+    if let Some(rest) = pattern.strip_prefix("*/") {
+        pattern = rest.to_string();
+    }
+    if is_absolute(&pattern) {
+        let base: Vec<&str> = pattern.split('/').take_while(|part| !part.contains(is_glob_meta)).collect();
+        let base = PathBuf::from(if base.len() == 1 { "/".to_string() } else { base.join("/") });
+        return (pattern, dir_only, Some(base));
+    }
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.starts_with("**/") {
+        (pattern.to_string(), dir_only, None)
+    } else {
+        (format!("**/{}", pattern), dir_only, None)
+    }
+}
+
+// 规则同时匹配路径本身及其子路径
+fn subtree(glob: String, dir_only: bool) -> Vec<(String, Target)> {
+    let children = format!("{}/**", glob);
+    vec![(glob, if dir_only { Target::Dir } else { Target::Any }), (children, Target::Any)]
+}
+
+// 类型规则：exe、.exe 按扩展名匹配，含通配符时按文件名匹配（如 ~$*.docx）
+fn type_to_glob(pattern: &str) -> String {
+    if pattern.contains(is_glob_meta) {
+        format!("**/{}", pattern)
+    } else {
+        format!("**/*.{}", pattern.trim_start_matches('.'))
+    }
+}
+
+impl PathRules {
+    /// 按 内置规则、exclude_paths、exclude_types、include_paths 的顺序组合，后面的规则优先
+    pub fn new(exclude_paths: &[String], exclude_types: &[String], include_paths: &[String]) -> Self {
+        let mut rules = Self::default();
+        let mut builder = GlobSetBuilder::new();
+        let case_insensitive = cfg!(any(target_os = "windows", target_os = "macos"));
+        let mut add = |rules: &mut Self, rule: PathRule, globs: Vec<(String, Target)>, case_insensitive: bool| {
+            let built: Result<Vec<_>, _> = globs.into_iter()
+                .map(|(glob, target)| GlobBuilder::new(&glob).literal_separator(true).case_insensitive(case_insensitive).build().map(|glob| (glob, target)))
+                .collect();
+            match built {
+                Ok(built) => {
+                    for (glob, target) in built {
+                        builder.add(glob);
+                        rules.globs.push((rules.rules.len(), target));
+                    }
+                    rules.rules.push(rule);
+                }
+                Err(e) => println!("排除规则无效:{:?} {:?}", rule.pattern, e),
+            }
+        };
+        for pattern in BUILTIN_RULES {
+            let rule = PathRule { source: RuleSource::Hidden, pattern: pattern.to_string(), include: false };
+            add(&mut rules, rule, subtree(format!("**/{}", pattern), false), false);
+        }
+        let patterns = exclude_paths.iter().map(|p| (RuleSource::ExcludePaths, p))
+            .chain(exclude_types.iter().map(|p| (RuleSource::ExcludeTypes, p)));
+        for (source, pattern) in patterns {
+            let trimmed = pattern.trim();
+            let (include, body) = match trimmed.strip_prefix('!') {
+                Some(body) => (true, body),
+                None => (false, trimmed),
+            };
+            if body.is_empty() {
+                continue;
+            }
+            let rule = PathRule { source, pattern: trimmed.to_string(), include };
+            if source == RuleSource::ExcludeTypes {
+                // 类型规则只作用于文件，且不区分大小写
+                add(&mut rules, rule, vec![(type_to_glob(body), Target::File)], true);
+                continue;
+            }
+            let (glob, dir_only, base) = to_glob(body);
+            if include {
+                rules.include_bases.extend(base);
+            }
+            add(&mut rules, rule, subtree(glob, dir_only), case_insensitive);
+        }
+        for pattern in include_paths {
+            let pattern = pattern.trim();
+            if pattern.is_empty() {
+                continue;
+            }
+            let (glob, dir_only, base) = to_glob(pattern);
+            rules.include_bases.extend(base);
+            let rule = PathRule { source: RuleSource::IncludePaths, pattern: pattern.to_string(), include: true };
+            add(&mut rules, rule, subtree(glob, dir_only), case_insensitive);
+        }
+        rules.set = builder.build().unwrap_or_default();
+        rules
+    }
+
+    /// 最后一条命中的规则，未命中返回 None
+    pub fn matched(&self, path: &Path, is_dir: bool) -> Option<&PathRule> {
+        self.set.matches(path).into_iter()
+            .filter_map(|i| {
+                let (rule, target) = self.globs[i];
+                let ok = match target {
+                    Target::Any => true,
+                    Target::Dir => is_dir,
+                    Target::File => !is_dir,
+                };
+                ok.then_some(rule)
+            })
+            .max()
+            .map(|rule| &self.rules[rule])
+    }
+
+    /// 路径是否被排除
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.matched(path, is_dir).is_some_and(|rule| !rule.include)
+    }
+
+    /// 被排除的目录下有包含规则时，遍历仍需进入该目录
+    pub fn has_include_under(&self, dir: &Path) -> bool {
+        self.include_bases.iter().any(|base| base.starts_with(dir) && base != dir)
+    }
+}
+
+#[test]
+fn test_path_rules() {
+    let exclude_paths = vec![
+        "*/dist".to_string(),
+        "*/src-tauri/target".to_string(),
+        "/data/Library".to_string(),
+        "**/*.tmp".to_string(),
+        "build/".to_string(),
+        "!/data/Library/Notes".to_string(),
+    ];
+    let exclude_types = vec!["exe".to_string(), "~$*.docx".to_string()];
+    let include_paths = vec!["/data/.config/app".to_string()];
+    let rules = PathRules::new(&exclude_paths, &exclude_types, &include_paths);
+    let excluded = |path: &str, is_dir: bool| rules.is_excluded(Path::new(path), is_dir);
+    // */dist 只匹配名为 dist 的目录
+    assert!(excluded("/data/web/dist", true));
+    assert!(excluded("/data/web/dist/app.js", false));
+    assert!(!excluded("/data/web/distribution", true));
+    assert!(!excluded("/data/redistributables", true));
+    assert!(excluded("/data/a/b/c.tmp", false));
+    assert!(excluded("/data/lark/src-tauri/target/debug", true));
+    assert!(!excluded("/data/lark/target", true));
+    // / 结尾只匹配目录
+    assert!(excluded("/data/build", true));
+    assert!(!excluded("/data/build", false));
+    // 绝对路径规则及重新包含
+    assert!(excluded("/data/Library/Caches/x", false));
+    assert!(!excluded("/data/Library/Notes/a.md", false));
+    assert!(rules.has_include_under(Path::new("/data/Library")));
+    assert!(!rules.has_include_under(Path::new("/data/web")));
+    // 类型规则只作用于文件
+    assert!(excluded("/data/SETUP.EXE", false));
+    assert!(!excluded("/data/tools.exe", true));
+    assert!(excluded("/data/~$报告.docx", false));
+    assert!(!excluded("/data/报告.docx", false));
+    // 隐藏文件可以被 include 覆盖
+    assert!(excluded("/data/.cache/x", false));
+    assert!(!excluded("/data/.config/app/settings.json", false));
+    assert!(rules.has_include_under(Path::new("/data/.config")));
+    let rule = rules.matched(Path::new("/data/web/dist"), true).unwrap();
+    assert_eq!((rule.source, rule.pattern.as_str()), (RuleSource::ExcludePaths, "*/dist"));
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+
+// 忽略文件规则：按目录层级读取 .larkignore、.ignore、.gitignore，与 ripgrep 一致，子目录规则优先于上级目录
+
+/// Lark 专用忽略文件，始终生效
+pub const LARK_IGNORE_FILE: &str = ".larkignore";
+// 版本控制忽略文件，可按索引根目录关闭；同一目录下越靠前优先级越高
+const VCS_IGNORE_FILES: &[&str] = &[".ignore", ".gitignore"];
+
+// 一个目录下的忽略规则，bool 表示是否为版本控制忽略文件
+type DirRules = Arc<Vec<(bool, Gitignore)>>;
+// 缓存的目录数上限，超出时淘汰最久未使用的目录
+const CACHE_CAPACITY: usize = 4096;
+
+// 目录规则缓存，按最近使用顺序淘汰，文件监听长期运行时占用的内存有上限
+#[derive(Default)]
+struct DirCache {
+    tick: u64,
+    // 目录 -> (最近使用的序号, 规则)
+    entries: HashMap<PathBuf, (u64, DirRules)>,
+}
+
+impl DirCache {
+    fn get(&mut self, dir: &Path) -> Option<DirRules> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(dir).map(|(used, rules)| {
+            *used = tick;
+            rules.clone()
+        })
+    }
+
+    fn insert(&mut self, dir: PathBuf, rules: DirRules) {
+        if self.entries.len() >= CACHE_CAPACITY {
+            // 一次淘汰四分之一，避免每次插入都重新排序
+            let mut used: Vec<u64> = self.entries.values().map(|(used, _)| *used).collect();
+            let (_, threshold, _) = used.select_nth_unstable(CACHE_CAPACITY / 4);
+            let threshold = *threshold;
+            self.entries.retain(|_, (used, _)| *used > threshold);
+        }
+        self.tick += 1;
+        self.entries.insert(dir, (self.tick, rules));
+    }
+
+    fn remove(&mut self, dir: &Path) {
+        self.entries.remove(dir);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct IgnoreFiles {
+    switch: bool,
+    // 按索引根目录设置的开关，路径越长越优先
+    roots: Vec<(PathBuf, bool)>,
+    // 目录 -> 该目录下的忽略规则，文件监听时按需加载
+    cache: Arc<Mutex<DirCache>>,
+}
+
+impl IgnoreFiles {
+    pub fn new(switch: bool, roots: HashMap<String, bool>) -> Self {
+        let mut roots: Vec<(PathBuf, bool)> = roots.into_iter().map(|(root, on)| (PathBuf::from(root), on)).collect();
+        roots.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+        Self { switch, roots, cache: Default::default() }
+    }
+
+    /// 路径所在索引根目录是否遵循 .gitignore、.ignore
+    pub fn respect_vcs(&self, path: &Path) -> bool {
+        self.roots.iter()
+            .find(|(root, _)| path.starts_with(root))
+            .map_or(self.switch, |(_, on)| *on)
+    }
+
+    /// 构建遍历器，上级目录中的忽略文件同样生效；隐藏文件交由调用方过滤
+    pub fn walk_builder(&self, root: &Path) -> WalkBuilder {
+        let vcs = self.respect_vcs(root);
+        let mut builder = WalkBuilder::new(root);
+        builder.hidden(false)
+            .parents(true)
+            .ignore(vcs)
+            .git_ignore(vcs)
+            .git_global(false)
+            .git_exclude(false)
+            .require_git(false)
+            .add_custom_ignore_filename(LARK_IGNORE_FILE);
+        builder
+    }
+
+    /// 单个路径是否被忽略，路径本身或任一上级目录命中规则即忽略，用于文件监听
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignored_by(path, is_dir).is_some()
+    }
+
+    /// 忽略该路径的规则，返回 (忽略文件, 规则)
+    pub fn ignored_by(&self, path: &Path, is_dir: bool) -> Option<(PathBuf, String)> {
+        let vcs = self.respect_vcs(path);
+        path.ancestors()
+            .take_while(|p| p.parent().is_some())
+            .enumerate()
+            .find_map(|(i, p)| match self.matched(p, i > 0 || is_dir, vcs) {
+                Match::Ignore(rule) => Some(rule),
+                _ => None,
+            })
+    }
+
+    /// 忽略文件变化时清除所在目录的缓存
+    pub fn invalidate(&self, path: &Path) {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+        if name == LARK_IGNORE_FILE || VCS_IGNORE_FILES.contains(&name) {
+            if let Some(dir) = path.parent() {
+                self.cache.lock().unwrap().remove(dir);
+            }
+        }
+    }
+
+    // 从最近的目录开始向上查找，第一个命中的规则（忽略或 ! 取反）生效
+    fn matched(&self, path: &Path, is_dir: bool, vcs: bool) -> Match<(PathBuf, String)> {
+        for dir in path.ancestors().skip(1) {
+            for (is_vcs, rules) in self.dir_rules(dir).iter() {
+                if *is_vcs && !vcs {
+                    continue;
+                }
+                match rules.matched(path, is_dir) {
+                    Match::None => {}
+                    Match::Ignore(glob) => {
+                        let file = glob.from().map(Path::to_path_buf).unwrap_or_else(|| dir.to_path_buf());
+                        return Match::Ignore((file, glob.original().to_string()));
+                    }
+                    Match::Whitelist(_) => return Match::Whitelist(Default::default()),
+                }
+            }
+        }
+        Match::None
+    }
+
+    fn dir_rules(&self, dir: &Path) -> DirRules {
+        if let Some(rules) = self.cache.lock().unwrap().get(dir) {
+            return rules;
+        }
+        let mut rules = vec![];
+        for (is_vcs, name) in [(false, LARK_IGNORE_FILE)].into_iter().chain(VCS_IGNORE_FILES.iter().map(|name| (true, *name))) {
+            let file = dir.join(name);
+            if !file.is_file() {
+                continue;
+            }
+            let mut builder = GitignoreBuilder::new(dir);
+            if let Some(e) = builder.add(&file) {
+                println!("读取忽略文件失败:{:?} {:?}", file, e);
+            }
+            match builder.build() {
+                Ok(gitignore) => rules.push((is_vcs, gitignore)),
+                Err(e) => println!("解析忽略文件失败:{:?} {:?}", file, e),
+            }
+        }
+        let rules = Arc::new(rules);
+        self.cache.lock().unwrap().insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+}
+
+#[test]
+fn test_ignore_files() {
+    use std::fs;
+    let dir = std::env::temp_dir().join("lark_ignore_test");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("repo/target/debug")).unwrap();
+    fs::create_dir_all(dir.join("repo/src/gen")).unwrap();
+    fs::create_dir_all(dir.join("other/target")).unwrap();
+    fs::write(dir.join("repo/.gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+    fs::write(dir.join("repo/src/.larkignore"), "gen\n").unwrap();
+    for file in ["repo/target/debug/app", "repo/a.log", "repo/keep.log", "repo/src/main.rs", "repo/src/gen/api.rs", "other/target/x"] {
+        fs::write(dir.join(file), "").unwrap();
+    }
+    let walked = |ignore_files: &IgnoreFiles| {
+        let mut paths: Vec<String> = ignore_files.walk_builder(&dir).build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_file() && !entry.file_name().to_str().unwrap().starts_with('.'))
+            .map(|entry| entry.path().strip_prefix(&dir).unwrap().display().to_string())
+            .collect();
+        paths.sort();
+        paths
+    };
+    let ignore_files = IgnoreFiles::new(true, HashMap::new());
+    assert_eq!(walked(&ignore_files), vec!["other/target/x", "repo/keep.log", "repo/src/main.rs"]);
+    assert!(ignore_files.is_ignored(&dir.join("repo/target/debug/app"), false));
+    assert!(ignore_files.is_ignored(&dir.join("repo/a.log"), false));
+    assert!(!ignore_files.is_ignored(&dir.join("repo/keep.log"), false));
+    assert!(ignore_files.is_ignored(&dir.join("repo/src/gen"), true));
+    assert!(!ignore_files.is_ignored(&dir.join("other/target/x"), false));
+    assert_eq!(ignore_files.ignored_by(&dir.join("repo/a.log"), false), Some((dir.join("repo/.gitignore"), "*.log".to_string())));
+    // 按根目录关闭后只保留 .larkignore
+    let roots = HashMap::from([(dir.join("repo").display().to_string(), false)]);
+    let ignore_files = IgnoreFiles::new(true, roots);
+    assert!(!ignore_files.is_ignored(&dir.join("repo/a.log"), false));
+    assert!(ignore_files.is_ignored(&dir.join("repo/src/gen/api.rs"), false));
+    // 修改忽略文件后重新加载
+    fs::write(dir.join("repo/src/.larkignore"), "").unwrap();
+    ignore_files.invalidate(&dir.join("repo/src/.larkignore"));
+    assert!(!ignore_files.is_ignored(&dir.join("repo/src/gen/api.rs"), false));
+}
+
+#[test]
+fn test_dir_cache() {
+    let mut cache = DirCache::default();
+    for i in 0..CACHE_CAPACITY {
+        cache.insert(PathBuf::from(format!("/dir/{}", i)), Default::default());
+    }
+    // 最早加入但刚使用过的目录保留
+    assert!(cache.get(Path::new("/dir/0")).is_some());
+    cache.insert(PathBuf::from("/dir/new"), Default::default());
+    assert!(cache.entries.len() <= CACHE_CAPACITY);
+    assert!(cache.get(Path::new("/dir/0")).is_some());
+    assert!(cache.get(Path::new("/dir/1")).is_none());
+    assert!(cache.get(Path::new("/dir/new")).is_some());
+}
@@ -1,9 +1,23 @@
 pub mod dirs;
 pub mod window;
 
+#[cfg(target_os = "windows")]
 pub mod icons;
 pub mod database;
 pub mod string_factory;
+pub mod fuzzy_factory;
+pub mod query_factory;
+pub mod extract_factory;
+pub mod ignore_factory;
+pub mod glob_factory;
+pub mod volumes;
+pub mod shuangpin;
 pub mod json_factory;
 pub mod img_factory;
-pub mod file_factory;
\ No newline at end of file
+pub mod file_factory;
+#[cfg(target_os = "linux")]
+pub mod mounts;
+#[cfg(target_os = "linux")]
+pub mod desktop_entry;
+#[cfg(target_os = "linux")]
+pub mod appimage;
@@ -0,0 +1,63 @@
+use std::fs;
+
+/// 会被纳入索引的真实磁盘文件系统类型
+const REAL_FILESYSTEMS: [&str; 13] = [
+    "ext2", "ext3", "ext4", "xfs", "btrfs", "f2fs", "jfs", "reiserfs", "zfs",
+    "ntfs", "ntfs3", "vfat", "exfat",
+];
+
+#[derive(Debug, Clone)]
+pub struct MountPoint {
+    pub path: String,
+}
+
+/// /proc/self/mounts 和 mountinfo 中的空格等字符以八进制转义（如 \040）
+pub(crate) fn unescape_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let value = u8::from_str_radix(&path[i + 1..i + 4], 8).unwrap_or(b'?');
+            result.push(value);
+            i += 4;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// 读取当前挂载的真实文件系统，过滤 proc、tmpfs、overlay 等虚拟文件系统
+pub fn mounted_filesystems() -> Vec<MountPoint> {
+    let content = match fs::read_to_string("/proc/self/mounts") {
+        Ok(content) => content,
+        Err(e) => {
+            println!("读取挂载信息失败:{:?}", e);
+            return vec![];
+        }
+    };
+    let mut mounts: Vec<MountPoint> = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 3 {
+            continue;
+        }
+        let fs_type = fields[2];
+        let is_fuse_block = fs_type == "fuseblk";
+        if !REAL_FILESYSTEMS.contains(&fs_type) && !is_fuse_block {
+            continue;
+        }
+        if !fields[0].starts_with("/dev/") {
+            continue;
+        }
+        let path = unescape_mount_path(fields[1]);
+        // 同一个路径可能被重复挂载（如 btrfs 子卷），只保留一次
+        if mounts.iter().any(|m| m.path == path) {
+            continue;
+        }
+        mounts.push(MountPoint { path });
+    }
+    mounts
+}
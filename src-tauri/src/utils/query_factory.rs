@@ -0,0 +1,322 @@
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use std::path::{Path, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+
+// 文件搜索查询语法，关键词中的 ext:pdf、size:>50MB、modified:<7d、created:>2024-01-01、kind:folder、
+// in:~/Documents、path:projects/lark 解析为过滤条件，含空格的值用双引号括起，如 in:"~/My Documents"
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compare {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+impl Compare {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Compare::Gt => ">",
+            Compare::Ge => ">=",
+            Compare::Lt => "<",
+            Compare::Le => "<=",
+            Compare::Eq => "=",
+        }
+    }
+
+    // 时长与时间戳方向相反：modified:<7d 即修改时间晚于 7 天前
+    fn reverse(&self) -> Self {
+        match self {
+            Compare::Gt => Compare::Lt,
+            Compare::Ge => Compare::Le,
+            Compare::Lt => Compare::Gt,
+            Compare::Le => Compare::Ge,
+            Compare::Eq => Compare::Eq,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileKind {
+    Folder,
+    File,
+    Exts(&'static [&'static str]),
+}
+
+const IMAGE_EXTS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "webp", "svg", "ico", "tif", "tiff", "heic", "raw"];
+const VIDEO_EXTS: &[&str] = &["mp4", "mkv", "mov", "avi", "wmv", "flv", "webm", "m4v", "rmvb"];
+const AUDIO_EXTS: &[&str] = &["mp3", "flac", "wav", "aac", "ogg", "m4a", "wma", "ape"];
+const DOCUMENT_EXTS: &[&str] = &["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "txt", "md", "rtf", "csv", "pages", "numbers", "key"];
+const ARCHIVE_EXTS: &[&str] = &["zip", "rar", "7z", "tar", "gz", "bz2", "xz", "zst", "tgz", "dmg", "iso"];
+const CODE_EXTS: &[&str] = &["rs", "py", "js", "ts", "jsx", "tsx", "vue", "java", "kt", "go", "c", "h", "cpp", "hpp", "cs", "swift", "rb", "php", "sh", "html", "css", "json", "toml", "yaml", "yml", "sql"];
+
+/// 路径范围：Prefix 为目录及其子路径，Segment 为路径中连续的目录名，可含 * 和 ? 通配符
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathScope {
+    Prefix(String),
+    Segment(String),
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileFilter {
+    pub exts: Vec<String>,
+    pub size: Vec<(Compare, u64)>,
+    /// 修改时间，秒级时间戳
+    pub mtime: Vec<(Compare, i64)>,
+    /// 创建时间，秒级时间戳
+    pub ctime: Vec<(Compare, i64)>,
+    pub kind: Option<FileKind>,
+    pub paths: Vec<PathScope>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileQuery {
+    pub keyword: String,
+    pub filter: FileFilter,
+}
+
+/// 拆分搜索输入中的关键词和过滤条件，无法识别的 key:value 保留为关键词
+pub fn parse_query(input: &str) -> FileQuery {
+    let home_dir = tauri::api::path::home_dir().map_or(String::new(), |dir| dir.to_string_lossy().to_string());
+    parse_query_at(input, Local::now().timestamp(), &home_dir)
+}
+
+fn parse_query_at(input: &str, now: i64, home_dir: &str) -> FileQuery {
+    let mut query = FileQuery::default();
+    let mut keywords = vec![];
+    for token in split_tokens(input) {
+        let parsed = match token.split_once(':') {
+            Some((key, value)) if !value.is_empty() => apply_filter(&mut query.filter, &key.to_lowercase(), value, now, home_dir),
+            _ => false,
+        };
+        if !parsed {
+            keywords.push(token);
+        }
+    }
+    query.keyword = keywords.join(" ");
+    query
+}
+
+// 按空白拆分，key:"a b" 中引号内的空白不拆分，引号只在紧跟 : 时作为定界符
+fn split_tokens(input: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let token = match rest.find(":\"") {
+            Some(colon) if colon < end => match rest[colon + 2..].find('"') {
+                Some(close) => {
+                    let close = colon + 2 + close;
+                    let token = format!("{}:{}", &rest[..colon], &rest[colon + 2..close]);
+                    rest = &rest[close + 1..];
+                    token
+                }
+                None => {
+                    let token = rest[..end].to_string();
+                    rest = &rest[end..];
+                    token
+                }
+            },
+            _ => {
+                let token = rest[..end].to_string();
+                rest = &rest[end..];
+                token
+            }
+        };
+        tokens.push(token);
+        rest = rest.trim_start();
+    }
+    tokens
+}
+
+// 统一路径分隔符并去掉末尾的分隔符，根目录保留
+fn normalize_path(path: &str) -> String {
+    let path = path.replace(['/', '\\'], MAIN_SEPARATOR_STR);
+    let trimmed = path.trim_end_matches(MAIN_SEPARATOR);
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        path
+    } else {
+        trimmed.to_string()
+    }
+}
+
+// in: 的值为绝对路径或 ~ 开头时按目录前缀，否则与 path: 相同按目录名匹配
+fn parse_scope(key: &str, value: &str, home_dir: &str) -> Option<PathScope> {
+    if key == "in" {
+        let expanded = match value.strip_prefix('~') {
+            Some(rest) if !home_dir.is_empty() && (rest.is_empty() || rest.starts_with(['/', '\\'])) => format!("{}{}", home_dir, rest),
+            _ => value.to_string(),
+        };
+        let path = normalize_path(&expanded);
+        if Path::new(&path).is_absolute() {
+            return Some(PathScope::Prefix(path));
+        }
+    }
+    let segment = normalize_path(value).trim_matches(MAIN_SEPARATOR).to_string();
+    if segment.is_empty() {
+        return None;
+    }
+    Some(PathScope::Segment(segment))
+}
+
+fn apply_filter(filter: &mut FileFilter, key: &str, value: &str, now: i64, home_dir: &str) -> bool {
+    match key {
+        "in" | "path" => parse_scope(key, value, home_dir).map(|scope| filter.paths.push(scope)).is_some(),
+        "ext" => {
+            let exts: Vec<String> = value.split([',', ';', '|'])
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .filter(|ext| !ext.is_empty())
+                .collect();
+            filter.exts.extend(exts);
+            true
+        }
+        "size" => {
+            let (compare, value) = parse_compare(value);
+            match parse_size(value) {
+                Some(size) => {
+                    filter.size.push((compare, size));
+                    true
+                }
+                None => false,
+            }
+        }
+        "modified" | "mtime" | "dm" => parse_time(value, now).map(|times| filter.mtime.extend(times)).is_some(),
+        "created" | "ctime" | "dc" => parse_time(value, now).map(|times| filter.ctime.extend(times)).is_some(),
+        "kind" | "type" => {
+            let kind = match value.to_lowercase().as_str() {
+                "folder" | "dir" | "directory" => FileKind::Folder,
+                "file" => FileKind::File,
+                "image" | "picture" => FileKind::Exts(IMAGE_EXTS),
+                "video" => FileKind::Exts(VIDEO_EXTS),
+                "audio" | "music" => FileKind::Exts(AUDIO_EXTS),
+                "doc" | "document" => FileKind::Exts(DOCUMENT_EXTS),
+                "archive" | "zip" => FileKind::Exts(ARCHIVE_EXTS),
+                "code" => FileKind::Exts(CODE_EXTS),
+                _ => return false,
+            };
+            filter.kind = Some(kind);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn parse_compare(value: &str) -> (Compare, &str) {
+    for (prefix, compare) in [(">=", Compare::Ge), ("<=", Compare::Le), (">", Compare::Gt), ("<", Compare::Lt), ("=", Compare::Eq)] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (compare, rest);
+        }
+    }
+    (Compare::Eq, value)
+}
+
+// 50MB、1.5g、512k、100（字节），按 1024 进制
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.to_lowercase();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let unit: u64 = match unit {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        "t" | "tb" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * unit as f64) as u64)
+}
+
+// 时长：30min、12h、7d、2w、3mo、1y
+fn parse_duration(value: &str) -> Option<i64> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    let seconds = match unit {
+        "s" => 1,
+        "min" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        "mo" => 30 * 86400,
+        "y" => 365 * 86400,
+        _ => return None,
+    };
+    Some(number * seconds)
+}
+
+fn day_start(date: NaiveDate) -> Option<i64> {
+    Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest().map(|time| time.timestamp())
+}
+
+// 时间条件：today、yesterday、时长（距今）或日期（2024-01-01），转为时间戳比较
+fn parse_time(value: &str, now: i64) -> Option<Vec<(Compare, i64)>> {
+    let (compare, value) = parse_compare(value);
+    let value = value.to_lowercase();
+    let today = Local.timestamp_opt(now, 0).single()?.date_naive();
+    let date = match value.as_str() {
+        "today" => Some(today),
+        "yesterday" => today.checked_sub_signed(Duration::days(1)),
+        _ => NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok(),
+    };
+    if let Some(date) = date {
+        let start = day_start(date)?;
+        let end = day_start(date.succ_opt()?)?;
+        // 日期按整天计算：=当天，>当天之后，<当天之前
+        return Some(match compare {
+            Compare::Eq => vec![(Compare::Ge, start), (Compare::Lt, end)],
+            Compare::Gt => vec![(Compare::Ge, end)],
+            Compare::Ge => vec![(Compare::Ge, start)],
+            Compare::Lt => vec![(Compare::Lt, start)],
+            Compare::Le => vec![(Compare::Lt, end)],
+        });
+    }
+    let duration = parse_duration(&value)?;
+    // 不带比较符的时长视为“最近”
+    let compare = if compare == Compare::Eq { Compare::Lt } else { compare };
+    Some(vec![(compare.reverse(), now - duration)])
+}
+
+#[test]
+fn test_parse_query() {
+    let now = Local.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap().timestamp();
+    let query = parse_query_at("报告 ext:pdf,.DOCX size:>50MB modified:<7d kind:folder", now, "/home/user");
+    assert_eq!(query.keyword, "报告");
+    assert_eq!(query.filter.exts, vec!["pdf", "docx"]);
+    assert_eq!(query.filter.size, vec![(Compare::Gt, 50 * 1024 * 1024)]);
+    assert_eq!(query.filter.mtime, vec![(Compare::Gt, now - 7 * 86400)]);
+    assert_eq!(query.filter.kind, Some(FileKind::Folder));
+
+    let day = Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap().timestamp();
+    let query = parse_query_at("created:2024-01-01 size:1.5k", now, "/home/user");
+    assert_eq!(query.keyword, "");
+    assert_eq!(query.filter.ctime, vec![(Compare::Ge, day), (Compare::Lt, day + 86400)]);
+    assert_eq!(query.filter.size, vec![(Compare::Eq, 1536)]);
+
+    // 无法识别的条件保留为关键词
+    let query = parse_query_at("C:\\Users size:big kind:unknown http://a", now, "/home/user");
+    assert_eq!(query.keyword, "C:\\Users size:big kind:unknown http://a");
+    assert_eq!(query.filter, FileFilter::default());
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_parse_path_scope() {
+    let query = parse_query_at("in:~/Documents report path:projects/lark/", 0, "/home/user");
+    assert_eq!(query.keyword, "report");
+    assert_eq!(query.filter.paths, vec![
+        PathScope::Prefix("/home/user/Documents".to_string()),
+        PathScope::Segment("projects/lark".to_string()),
+    ]);
+    let query = parse_query_at(r#"in:"~/My Documents" 季度 报告 in:src"#, 0, "/home/user");
+    assert_eq!(query.keyword, "季度 报告");
+    assert_eq!(query.filter.paths, vec![
+        PathScope::Prefix("/home/user/My Documents".to_string()),
+        PathScope::Segment("src".to_string()),
+    ]);
+    assert_eq!(parse_query_at("in:/ a", 0, "/home/user").filter.paths, vec![PathScope::Prefix("/".to_string())]);
+    // 没有闭合的引号不作为定界符
+    let query = parse_query_at(r#"in:"~/a b"#, 0, "/home/user");
+    assert_eq!(query.keyword, "b");
+    assert_eq!(query.filter.paths, vec![PathScope::Segment("\"~/a".to_string())]);
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+// 双拼输入转换为全拼，用于以双拼方式搜索拼音
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PinyinInputMode {
+    #[default]
+    Full,
+    Microsoft,
+    Xiaohe,
+}
+
+// 全部合法音节（ü 写作 v）
+const SYLLABLES: &[&str] = &[
+    "a", "ai", "an", "ang", "ao", "e", "ei", "en", "eng", "er", "o", "ou",
+    "ba", "bai", "ban", "bang", "bao", "bei", "ben", "beng", "bi", "bian", "biao", "bie", "bin", "bing", "bo", "bu",
+    "pa", "pai", "pan", "pang", "pao", "pei", "pen", "peng", "pi", "pian", "piao", "pie", "pin", "ping", "po", "pou", "pu",
+    "ma", "mai", "man", "mang", "mao", "me", "mei", "men", "meng", "mi", "mian", "miao", "mie", "min", "ming", "miu", "mo", "mou", "mu",
+    "fa", "fan", "fang", "fei", "fen", "feng", "fo", "fou", "fu",
+    "da", "dai", "dan", "dang", "dao", "de", "dei", "den", "deng", "di", "dia", "dian", "diao", "die", "ding", "diu", "dong", "dou", "du", "duan", "dui", "dun", "duo",
+    "ta", "tai", "tan", "tang", "tao", "te", "tei", "teng", "ti", "tian", "tiao", "tie", "ting", "tong", "tou", "tu", "tuan", "tui", "tun", "tuo",
+    "na", "nai", "nan", "nang", "nao", "ne", "nei", "nen", "neng", "ni", "nian", "niang", "niao", "nie", "nin", "ning", "niu", "nong", "nou", "nu", "nuan", "nun", "nuo", "nv", "nve",
+    "la", "lai", "lan", "lang", "lao", "le", "lei", "leng", "li", "lia", "lian", "liang", "liao", "lie", "lin", "ling", "liu", "lo", "long", "lou", "lu", "luan", "lun", "luo", "lv", "lve",
+    "ga", "gai", "gan", "gang", "gao", "ge", "gei", "gen", "geng", "gong", "gou", "gu", "gua", "guai", "guan", "guang", "gui", "gun", "guo",
+    "ka", "kai", "kan", "kang", "kao", "ke", "kei", "ken", "keng", "kong", "kou", "ku", "kua", "kuai", "kuan", "kuang", "kui", "kun", "kuo",
+    "ha", "hai", "han", "hang", "hao", "he", "hei", "hen", "heng", "hong", "hou", "hu", "hua", "huai", "huan", "huang", "hui", "hun", "huo",
+    "ji", "jia", "jian", "jiang", "jiao", "jie", "jin", "jing", "jiong", "jiu", "ju", "juan", "jue", "jun",
+    "qi", "qia", "qian", "qiang", "qiao", "qie", "qin", "qing", "qiong", "qiu", "qu", "quan", "que", "qun",
+    "xi", "xia", "xian", "xiang", "xiao", "xie", "xin", "xing", "xiong", "xiu", "xu", "xuan", "xue", "xun",
+    "zha", "zhai", "zhan", "zhang", "zhao", "zhe", "zhei", "zhen", "zheng", "zhi", "zhong", "zhou", "zhu", "zhua", "zhuai", "zhuan", "zhuang", "zhui", "zhun", "zhuo",
+    "cha", "chai", "chan", "chang", "chao", "che", "chen", "cheng", "chi", "chong", "chou", "chu", "chua", "chuai", "chuan", "chuang", "chui", "chun", "chuo",
+    "sha", "shai", "shan", "shang", "shao", "she", "shei", "shen", "sheng", "shi", "shou", "shu", "shua", "shuai", "shuan", "shuang", "shui", "shun", "shuo",
+    "ran", "rang", "rao", "re", "ren", "reng", "ri", "rong", "rou", "ru", "rua", "ruan", "rui", "run", "ruo",
+    "za", "zai", "zan", "zang", "zao", "ze", "zei", "zen", "zeng", "zi", "zong", "zou", "zu", "zuan", "zui", "zun", "zuo",
+    "ca", "cai", "can", "cang", "cao", "ce", "cen", "ceng", "ci", "cong", "cou", "cu", "cuan", "cui", "cun", "cuo",
+    "sa", "sai", "san", "sang", "sao", "se", "sen", "seng", "si", "song", "sou", "su", "suan", "sui", "sun", "suo",
+    "ya", "yan", "yang", "yao", "ye", "yi", "yin", "ying", "yo", "yong", "you", "yu", "yuan", "yue", "yun",
+    "wa", "wai", "wan", "wang", "wei", "wen", "weng", "wo", "wu",
+];
+
+// 韵母键位，同一个键可能对应多个韵母，按能否与声母拼成合法音节区分
+fn finals(mode: PinyinInputMode, key: char) -> &'static [&'static str] {
+    match (mode, key) {
+        (_, 'a') => &["a"],
+        (_, 'e') => &["e"],
+        (_, 'i') => &["i"],
+        (_, 'u') => &["u"],
+        (_, 'o') => &["uo", "o"],
+        (_, 'q') => &["iu"],
+        (_, 'r') => &["uan", "er"],
+        (_, 's') => &["ong", "iong"],
+        (_, 'f') => &["en"],
+        (_, 'g') => &["eng"],
+        (_, 'h') => &["ang"],
+        (_, 'j') => &["an"],
+        (_, 'm') => &["ian"],
+        (PinyinInputMode::Microsoft, 'w') => &["ua", "ia"],
+        (PinyinInputMode::Microsoft, 't') => &["ue"],
+        (PinyinInputMode::Microsoft, 'y') => &["uai", "v"],
+        (PinyinInputMode::Microsoft, 'p') => &["un"],
+        (PinyinInputMode::Microsoft, 'd') => &["uang", "iang"],
+        (PinyinInputMode::Microsoft, 'k') => &["ao"],
+        (PinyinInputMode::Microsoft, 'l') => &["ai"],
+        (PinyinInputMode::Microsoft, ';') => &["ing"],
+        (PinyinInputMode::Microsoft, 'z') => &["ei"],
+        (PinyinInputMode::Microsoft, 'x') => &["ie"],
+        (PinyinInputMode::Microsoft, 'c') => &["iao"],
+        (PinyinInputMode::Microsoft, 'v') => &["ui", "ve"],
+        (PinyinInputMode::Microsoft, 'b') => &["ou"],
+        (PinyinInputMode::Microsoft, 'n') => &["in"],
+        (PinyinInputMode::Xiaohe, 'w') => &["ei"],
+        (PinyinInputMode::Xiaohe, 't') => &["ue", "ve"],
+        (PinyinInputMode::Xiaohe, 'y') => &["un"],
+        (PinyinInputMode::Xiaohe, 'p') => &["ie"],
+        (PinyinInputMode::Xiaohe, 'd') => &["ai"],
+        (PinyinInputMode::Xiaohe, 'k') => &["ing", "uai"],
+        (PinyinInputMode::Xiaohe, 'l') => &["iang", "uang"],
+        (PinyinInputMode::Xiaohe, 'z') => &["ou"],
+        (PinyinInputMode::Xiaohe, 'x') => &["ia", "ua"],
+        (PinyinInputMode::Xiaohe, 'c') => &["ao"],
+        (PinyinInputMode::Xiaohe, 'v') => &["ui", "v"],
+        (PinyinInputMode::Xiaohe, 'b') => &["in"],
+        (PinyinInputMode::Xiaohe, 'n') => &["iao"],
+        _ => &[],
+    }
+}
+
+// 单字母声母
+const INITIALS: &str = "bpmfdtnlgkhjqxrzcsyw";
+
+// 声母键位，None 表示非声母键
+fn initial(mode: PinyinInputMode, key: char) -> Option<&'static str> {
+    match key {
+        'u' => Some("sh"),
+        'i' => Some("ch"),
+        'v' => Some("zh"),
+        // 零声母：微软双拼以 o 引导，小鹤双拼以韵母首字母引导
+        'o' if mode == PinyinInputMode::Microsoft => Some(""),
+        'a' | 'e' | 'o' if mode == PinyinInputMode::Xiaohe => Some(""),
+        _ => INITIALS.find(key).map(|i| &INITIALS[i..i + 1]),
+    }
+}
+
+fn decode_syllable(mode: PinyinInputMode, first: char, second: char) -> Option<String> {
+    let init = initial(mode, first)?;
+    let mut candidates: Vec<String> = finals(mode, second).iter().map(|f| format!("{}{}", init, f)).collect();
+    if init.is_empty() && mode == PinyinInputMode::Xiaohe {
+        // 小鹤零声母：单韵母双击（aa），双字母韵母直接输入（ai、ou），三字母韵母首字母加韵母键（ah）
+        candidates.retain(|c| c.starts_with(first));
+        candidates.push(if first == second { first.to_string() } else { format!("{}{}", first, second) });
+    } else if init.is_empty() && second == 'o' {
+        candidates.push("o".to_string());
+    }
+    candidates.into_iter().find(|c| SYLLABLES.contains(&c.as_str()))
+}
+
+/// 双拼输入转全拼，如小鹤双拼 isqk -> chongqing；末尾单键按声母处理，便于前缀匹配。
+/// 全拼模式或无法按双拼解析时返回 None
+pub fn shuangpin_to_pinyin(input: &str, mode: PinyinInputMode) -> Option<String> {
+    if mode == PinyinInputMode::Full || input.is_empty() {
+        return None;
+    }
+    let keys: Vec<char> = input.to_lowercase().chars().collect();
+    let mut pinyin = String::new();
+    for pair in keys.chunks(2) {
+        match pair {
+            [first, second] => pinyin.push_str(&decode_syllable(mode, *first, *second)?),
+            [first] => match initial(mode, *first)? {
+                "" if mode == PinyinInputMode::Xiaohe => pinyin.push(*first),
+                init => pinyin.push_str(init),
+            },
+            _ => {}
+        }
+    }
+    Some(pinyin)
+}
+
+#[test]
+fn test_shuangpin_to_pinyin() {
+    assert_eq!(shuangpin_to_pinyin("isqk", PinyinInputMode::Xiaohe), Some("chongqing".to_string()));
+    assert_eq!(shuangpin_to_pinyin("ysxc", PinyinInputMode::Microsoft), Some("yongxiao".to_string()));
+    assert_eq!(shuangpin_to_pinyin("is", PinyinInputMode::Microsoft), Some("chong".to_string()));
+    assert_eq!(shuangpin_to_pinyin("vsqk", PinyinInputMode::Xiaohe), Some("zhongqing".to_string()));
+    assert_eq!(shuangpin_to_pinyin("vsq;", PinyinInputMode::Microsoft), Some("zhongqing".to_string()));
+    // 零声母
+    assert_eq!(shuangpin_to_pinyin("ahaa", PinyinInputMode::Xiaohe), Some("anga".to_string()));
+    assert_eq!(shuangpin_to_pinyin("ohoo", PinyinInputMode::Microsoft), Some("ango".to_string()));
+    assert_eq!(shuangpin_to_pinyin("er", PinyinInputMode::Xiaohe), Some("er".to_string()));
+    assert_eq!(shuangpin_to_pinyin("or", PinyinInputMode::Microsoft), Some("er".to_string()));
+    // ü
+    assert_eq!(shuangpin_to_pinyin("lv", PinyinInputMode::Xiaohe), Some("lv".to_string()));
+    assert_eq!(shuangpin_to_pinyin("ly", PinyinInputMode::Microsoft), Some("lv".to_string()));
+    // 末尾单键
+    assert_eq!(shuangpin_to_pinyin("ysx", PinyinInputMode::Microsoft), Some("yongx".to_string()));
+    assert_eq!(shuangpin_to_pinyin("code", PinyinInputMode::Full), None);
+    assert_eq!(shuangpin_to_pinyin("aq", PinyinInputMode::Xiaohe), None);
+}
@@ -2,7 +2,8 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use crypto::digest::Digest;
 use crypto::md5::Md5;
-use pinyin::ToPinyin;
+use pinyin::ToPinyinMulti;
+use std::io::{self, Read};
 
 pub fn base64_encode(bytes: &[u8]) -> String {
     general_purpose::STANDARD.encode(bytes)
@@ -25,24 +26,72 @@ pub fn md5_by_bytes(bytes: &[u8]) -> String {
     hasher.result_str()
 }
 
+/// 分块读取计算 md5，不把整个文件读入内存
+pub fn md5_by_reader<R: Read>(reader: &mut R) -> io::Result<String> {
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0u8; 256 * 1024];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.input(&buffer[..n]);
+    }
+    Ok(hasher.result_str())
+}
+
+// 多音字读音组合数上限，超出后其余汉字只取常用读音
+const MAX_PINYIN_VARIANTS: usize = 16;
+
+/// 生成拼音和首字母，多音字的各种读音组合以空格分隔，如 重庆 -> ("zhongqing chongqing", "zq cq")
 #[allow(unused)]
 pub fn text_to_pinyin(text: &str) -> (String, String) {
-    let mut pinyin = String::new();
-    let mut abb = String::new();
-    for (i, p) in text.to_pinyin().enumerate() {
+    let mut variants: Vec<(String, String)> = vec![(String::new(), String::new())];
+    for (ori_char, p) in text.chars().zip(text.to_pinyin_multi()) {
+        let mut readings: Vec<(String, String)> = vec![];
         match p {
-            Some(char) => {
-                pinyin.push_str(&char.plain());
-                abb.push_str(&char.first_letter());
+            Some(multi) => {
+                for char in multi {
+                    // ü 统一写作 v，与键盘输入习惯一致
+                    let reading = (char.plain().replace('ü', "v"), char.first_letter().replace('ü', "v"));
+                    if !readings.contains(&reading) {
+                        readings.push(reading);
+                    }
+                }
             }
             None => {
-                let ori_char = text.chars().nth(i).unwrap();
-                pinyin.push_str(ori_char.to_string().as_str());
-                if ori_char.is_ascii_uppercase(){
-                    abb.push_str(ori_char.to_string().as_str());
+                // 空格用作读音分隔符，不计入拼音
+                if ori_char.is_whitespace() {
+                    continue;
                 }
+                // 保留大写字母和数字，便于 "bg2024" 这类混合输入匹配 "报告2024"
+                let abb = if ori_char.is_ascii_uppercase() || ori_char.is_ascii_digit() {
+                    ori_char.to_string()
+                } else {
+                    String::new()
+                };
+                readings.push((ori_char.to_string(), abb));
             }
         }
+        if readings.is_empty() {
+            continue;
+        }
+        if variants.len() * readings.len() > MAX_PINYIN_VARIANTS {
+            readings.truncate(1);
+        }
+        variants = variants.iter()
+            .flat_map(|(pinyin, abb)| readings.iter().map(move |(p, a)| (format!("{}{}", pinyin, p), format!("{}{}", abb, a))))
+            .collect();
+    }
+    let mut pinyin: Vec<String> = vec![];
+    let mut abb: Vec<String> = vec![];
+    for (p, a) in variants {
+        if !pinyin.contains(&p) {
+            pinyin.push(p);
+        }
+        if !abb.contains(&a) {
+            abb.push(a);
+        }
     }
-    (pinyin, abb)
+    (pinyin.join(" "), abb.join(" "))
 }
@@ -0,0 +1,182 @@
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use crate::utils::mounts::unescape_mount_path;
+
+// 可移动磁盘和外接硬盘：每个卷单独保存索引，按文件系统 UUID 区分，挂载时加入搜索，卸载后隐藏
+
+/// 已挂载的卷
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Volume {
+    /// 文件系统 UUID，Windows 下为卷序列号
+    pub uuid: String,
+    pub label: String,
+    /// 挂载路径
+    pub path: String,
+}
+
+// 当前挂载并已加入搜索的卷，由卷监听维护
+static MOUNTED: Mutex<Vec<Volume>> = Mutex::new(Vec::new());
+
+pub fn mounted() -> Vec<Volume> {
+    MOUNTED.lock().unwrap().clone()
+}
+
+pub fn set_mounted(volumes: Vec<Volume>) {
+    *MOUNTED.lock().unwrap() = volumes;
+}
+
+/// 卷索引数据库文件名，UUID 中只保留字母、数字和 -
+pub fn db_file_name(uuid: &str) -> String {
+    let uuid: String = uuid.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    format!("volume_{}.sqlite", uuid)
+}
+
+/// 解析 mountinfo 的一行，返回 (挂载路径, 文件系统类型, 设备)
+#[cfg(target_os = "linux")]
+fn parse_mountinfo_line(line: &str) -> Option<(String, String, String)> {
+    // 36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+    let (left, right) = line.split_once(" - ")?;
+    let path = left.split_whitespace().nth(4)?;
+    let mut right = right.split_whitespace();
+    let fs_type = right.next()?;
+    let device = right.next()?;
+    Some((unescape_mount_path(path), fs_type.to_string(), unescape_mount_path(device)))
+}
+
+// udisks 自动挂载的目录
+#[cfg(target_os = "linux")]
+fn is_udisks_mount(path: &str) -> bool {
+    path.starts_with("/media/") || path.starts_with("/run/media/")
+}
+
+// 块设备是否为可移动设备，分区读取所在磁盘的标记
+#[cfg(target_os = "linux")]
+fn is_removable_device(device: &Path) -> bool {
+    let name = match device.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name.to_string(),
+        None => return false,
+    };
+    let sys = Path::new("/sys/class/block").join(&name);
+    let flag = |dir: &Path| fs::read_to_string(dir.join("removable")).is_ok_and(|v| v.trim() == "1");
+    if flag(&sys) {
+        return true;
+    }
+    fs::canonicalize(&sys).ok()
+        .and_then(|dir| dir.parent().map(|parent| parent.to_path_buf()))
+        .is_some_and(|parent| flag(&parent))
+}
+
+// 设备对应的文件系统 UUID
+#[cfg(target_os = "linux")]
+fn device_uuid(device: &Path) -> Option<String> {
+    let device = fs::canonicalize(device).ok()?;
+    fs::read_dir("/dev/disk/by-uuid").ok()?.filter_map(Result::ok)
+        .find(|entry| fs::canonicalize(entry.path()).is_ok_and(|target| target == device))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+}
+
+/// 当前挂载的可移动卷：Linux 为 udisks 挂载目录或可移动块设备，macOS 为 /Volumes 下的卷，Windows 为可移动驱动器
+pub fn removable_volumes() -> Vec<Volume> {
+    let mut volumes = Vec::new();
+    #[cfg(target_os = "linux")]{
+        let content = match fs::read_to_string("/proc/self/mountinfo") {
+            Ok(content) => content,
+            Err(e) => {
+                println!("读取挂载信息失败:{:?}", e);
+                return volumes;
+            }
+        };
+        for (path, _, device) in content.lines().filter_map(parse_mountinfo_line) {
+            if !device.starts_with("/dev/") || volumes.iter().any(|v: &Volume| v.path == path) {
+                continue;
+            }
+            let device = Path::new(&device);
+            if !is_udisks_mount(&path) && !is_removable_device(device) {
+                continue;
+            }
+            // 没有 UUID 的文件系统无法在重新挂载时识别；udisks 以卷标作为挂载目录名
+            if let Some(uuid) = device_uuid(device) {
+                let label = Path::new(&path).file_name().map_or(String::new(), |name| name.to_string_lossy().to_string());
+                volumes.push(Volume { uuid, label, path });
+            }
+        }
+    }
+    #[cfg(target_os = "macos")]{
+        use std::process::Command;
+        let entries = match std::fs::read_dir("/Volumes") {
+            Ok(entries) => entries,
+            Err(_) => return volumes,
+        };
+        for entry in entries.filter_map(Result::ok) {
+            // 启动卷在 /Volumes 下是指向 / 的符号链接
+            if entry.file_type().map_or(true, |t| t.is_symlink() || !t.is_dir()) {
+                continue;
+            }
+            let path = entry.path().to_string_lossy().to_string();
+            let output = match Command::new("diskutil").args(["info", "-plist", &path]).output() {
+                Ok(output) => output,
+                Err(_) => continue,
+            };
+            let info = match plist::Value::from_reader(std::io::Cursor::new(output.stdout)) {
+                Ok(plist::Value::Dictionary(info)) => info,
+                _ => continue,
+            };
+            let text = |key: &str| info.get(key).and_then(|v| v.as_string()).map(|v| v.to_string());
+            if let Some(uuid) = text("VolumeUUID").or_else(|| text("DiskUUID")) {
+                let label = text("VolumeName").unwrap_or_else(|| entry.file_name().to_string_lossy().to_string());
+                volumes.push(Volume { uuid, label, path });
+            }
+        }
+    }
+    #[cfg(target_os = "windows")]{
+        use std::ffi::OsString;
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+        use winapi::um::fileapi::{GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW};
+        use winapi::um::winbase::DRIVE_REMOVABLE;
+        let drive_bits = unsafe { GetLogicalDrives() };
+        for i in 0..26 {
+            if (drive_bits & (1 << i)) == 0 {
+                continue;
+            }
+            let path = format!("{}:\\", (b'A' + i) as char);
+            let path_w: Vec<u16> = OsString::from(&path).encode_wide().chain(std::iter::once(0)).collect();
+            if unsafe { GetDriveTypeW(path_w.as_ptr()) } != DRIVE_REMOVABLE {
+                continue;
+            }
+            let mut name = [0u16; 261];
+            let mut serial = 0u32;
+            let ok = unsafe {
+                GetVolumeInformationW(
+                    path_w.as_ptr(), name.as_mut_ptr(), name.len() as u32, &mut serial,
+                    std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut(), 0,
+                )
+            };
+            // 未插入介质的读卡器等
+            if ok == 0 {
+                continue;
+            }
+            let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            let label = OsString::from_wide(&name[..len]).to_string_lossy().to_string();
+            volumes.push(Volume { uuid: format!("{:08X}", serial), label, path });
+        }
+    }
+    volumes
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_mountinfo() {
+    let line = r"97 29 8:17 / /run/media/user/My\040Disk rw,nosuid,nodev shared:52 - exfat /dev/sdb1 rw,fmask=0022";
+    let (path, fs_type, device) = parse_mountinfo_line(line).unwrap();
+    assert_eq!(path, "/run/media/user/My Disk");
+    assert_eq!(fs_type, "exfat");
+    assert_eq!(device, "/dev/sdb1");
+    assert!(is_udisks_mount(&path));
+    assert!(!is_udisks_mount("/home"));
+    assert_eq!(db_file_name("1A2B-3C4D/.."), "volume_1A2B-3C4D.sqlite");
+}
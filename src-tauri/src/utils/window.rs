@@ -54,6 +54,12 @@ pub fn set_window_show() -> String {
     "".to_string()
 }
 
+#[cfg(target_os = "linux")]
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_window_show() -> String {
+    "".to_string()
+}
+
 #[cfg(target_os = "macos")]
 pub fn register_global_hotkey() {
     unsafe {
@@ -18,7 +18,6 @@ import {
     modifyWindowSize,
     getWindowPosition,
     loadCustomComponent,
-    initAppHabitDB
 } from "./template.jsx";
 
 const SubpageComponent = React.lazy(() =>
@@ -27,6 +26,8 @@ const SubpageComponent = React.lazy(() =>
 const TemplateComponent = React.lazy(() =>
     import("./template.jsx").then((mod) => ({default: mod.TemplateComponent}))
 );
+// 应用打包方式的角标，普通应用不显示
+const packageBadges = {flatpak: "Flatpak", snap: "Snap", appimage: "AppImage"};
 
 const App = () => {
     // 键入值
@@ -62,8 +63,6 @@ const App = () => {
 
     const [insidePluginList, setInsidePluginList] = useState(pluginsComponent);
 
-    const [appHabitDB, setAppHabitDB] = useState(null);
-
     const [actionParent, setActionParent] = useState({})
 
     function initStatus(components) {
@@ -88,7 +87,7 @@ const App = () => {
         setKeyDown(event)
         if (!event.metaKey && event.key === "Enter") {
             if (keywordComponent) {
-                await confirmComponentSelected();
+                await confirmComponentSelected(undefined, undefined, event.shiftKey);
             }
         } else if (event.key === "Tab" && component) {
             // 当按下TAB键时，将焦点移动到下一个输入框
@@ -195,41 +194,33 @@ const App = () => {
     }
 
 
-    const getAppHabit = async (keyword) => {
-        // 获取用户习惯热点度
+    const recordLaunch = async (provider, resultId) => {
+        // 记录本次输入下选中的结果，用于按启动频率和时间排序
         try {
-            const res = await appHabitDB.getDataByKey(keyword);
-            return JSON.parse(res[0]?.habitData || "{}");
+            await invoke("record_launch", {query: inputValue, provider, resultId});
         } catch (err) {
-            return console.log('获取数据失败[appHabitDB]==>', err) || {};
+            console.log('保存启动记录失败==>', err);
         }
     };
-    const updateAppHabit = async (keyword, appName) => {
-        // 更新用户习惯热点度
-        try {
-            let habitData = await getAppHabit(keyword);
-            if (!habitData[appName]) {
-                habitData[appName] = 0;
-            }
-            habitData[appName] += 1;
-            return appHabitDB.update({keyword, habitData: JSON.stringify(habitData)});
-        } catch (err) {
-            return console.log('更新数据失败[appHabitDB]==>', err);
-        }
+    const launchTarget = (item) => {
+        // 搜索结果按路径记录，插件组件按标题记录
+        if (item.type === "app") return {provider: "app", resultId: item.data};
+        if (item.type === "file" || item.type === "duplicate") return {provider: "file", resultId: item.data};
+        return {provider: "component", resultId: item.title};
     };
     const initPoi = async () => {
         let window_position = await windowPosition.current
         await appWindow.setPosition(new LogicalPosition(window_position.x, window_position.y));
     };
 
-    async function confirmComponentSelected(index, metaStatus) {
+    async function confirmComponentSelected(index, metaStatus, shiftStatus) {
         // 组件确认选择后
         let currentComponent = keywordComponent[index !== undefined ? index : selectedIndex];
         setSelectedIndex(0);
         console.log(currentComponent)
         if (!currentComponent) return
         if (currentComponent.type === "component") {
-            await updateAppHabit(inputValue, currentComponent.title);
+            await recordLaunch("component", currentComponent.title);
             if (typeof currentComponent.icon == "string") {
                 setComponent(<div className='activateComponent'
                                   data-tauri-drag-region>{currentComponent.icon.slice(0, 4)}</div>);
@@ -241,7 +232,7 @@ const App = () => {
             setInputValue("");
             inputBox.current.focus();
         } else if (currentComponent.type === "subpage") {
-            await updateAppHabit(inputValue, currentComponent.title);
+            await recordLaunch("component", currentComponent.title);
             // 设置子页面的图标
             if (typeof currentComponent.icon == "string") {
                 setComponent(<div className='activateComponent'
@@ -263,7 +254,7 @@ const App = () => {
             });
         } else if (currentComponent.type === "app") {
             if (!fnDown || metaStatus === false) {
-                await updateAppHabit(inputValue, currentComponent.title);
+                await recordLaunch("app", currentComponent.data);
                 console.log(currentComponent)
                 await invoke("open_app", {appPath: currentComponent.data, appName: currentComponent.title});
                 initStatus();
@@ -332,12 +323,29 @@ const App = () => {
             };
             handle(currentComponent)
         } else if (currentComponent.type === "file") {
+            if (shiftStatus && currentComponent.is_dir) {
+                // Shift+Enter 在该文件夹内搜索
+                return setInputValue(`in:"${currentComponent.path}" `);
+            }
             if (!fnDown) {
+                await recordLaunch("file", currentComponent.data);
                 await invoke("open_file", {filePath: currentComponent.data});
             } else {
                 await invoke("open_explorer", {path: keywordComponent[selectedIndex].data});
             }
             initStatus();
+        } else if (currentComponent.type === "duplicate") {
+            // 重复文件：Shift+Enter 移到回收站，其余与文件搜索相同
+            if (shiftStatus) {
+                await invoke("trash_duplicate_file", {path: currentComponent.data});
+                return setKeywordComponent(keywordComponent.filter(item => item.data !== currentComponent.data));
+            } else if (!fnDown) {
+                await recordLaunch("file", currentComponent.data);
+                await invoke("open_file", {filePath: currentComponent.data});
+            } else {
+                await invoke("open_explorer", {path: currentComponent.data});
+            }
+            initStatus();
         }
         setKeywordComponent([])
     }
@@ -448,8 +456,8 @@ const App = () => {
             }
             console.log("搜索类型", searchType);
             let result = [];
-            // 输入有值且不在输入状态时,进行搜索
-            if (inputValue.trim() && !isComposing.status) {
+            // 输入有值且不在输入状态时,进行搜索；文件搜索未输入时列出最近文件
+            if ((inputValue.trim() || searchType === "file") && !isComposing.status) {
                 if (inputValue.trim() === "reIndex") {
                     await invoke("create_file_index", {})
                     return
@@ -549,6 +557,7 @@ const App = () => {
                                         <img src={`data:image/png;base64,${item.icon}`} style={{width: "100%"}}></img>;
                                 }
                                 item.data = item.path;
+                                item.badge = packageBadges[item.file_type];
                                 item.type = "app";
                                 result.push(item);
                             }
@@ -566,6 +575,14 @@ const App = () => {
                                 }
                                 result.push(item);
                             }
+                            if (searchType === "重复文件") {
+                                item = item.File;
+                                item.data = item.path;
+                                item.type = "duplicate";
+                                item.icon =
+                                    <img src={getMaterialFileIcon(item.file_type)} style={{width: "100%"}}></img>;
+                                result.push(item);
+                            }
                         }
                     }
 
@@ -581,10 +598,25 @@ const App = () => {
                         // 没有结果则进行web搜索
                         result = webSearchComponent(inputValue);
                     } else {
-                        // 有结果 则判断关键词在appHabit中的热度，根据热度再排序。其中在这个关键词下每启动一次该app，则热度+1
+                        // 有结果 则按启动记录的得分（启动次数随时间衰减，同一关键词下的启动额外加权）把插件组件插入应用结果中
+                        // 应用保持后端的顺序（别名、匹配程度和启动记录已排好），只按得分决定插件组件的位置
                         if (searchType === "app") {
-                            let habit = await getAppHabit(inputValue);
-                            result.sort((a, b) => (habit[b.title] || 0) - (habit[a.title] || 0));
+                            let scores = await invoke("get_frecency_scores", {
+                                query: inputValue,
+                                targets: result.map(launchTarget)
+                            });
+                            let scored = result.map((item, index) => ({item, score: scores[index] || 0}));
+                            let apps = scored.filter(({item}) => item.type === "app");
+                            let components = scored.filter(({item}) => item.type !== "app")
+                                .sort((a, b) => b.score - a.score);
+                            result = [];
+                            while (apps.length > 0 || components.length > 0) {
+                                if (apps.length === 0 || (components.length > 0 && components[0].score > apps[0].score)) {
+                                    result.push(components.shift().item);
+                                } else {
+                                    result.push(apps.shift().item);
+                                }
+                            }
                         }
                     }
 
@@ -654,8 +686,6 @@ const App = () => {
         // 输入框获取焦点
         inputBox.current.focus();
 
-        initAppHabitDB(appHabitDB, setAppHabitDB, dbList, setDbList);
-
         // 读取本地组件库，查看注册状态
         loadCustomComponent().then((result) => {
                 setPluginList(result);
@@ -679,9 +709,6 @@ const App = () => {
 
     }, []);
 
-    useEffect(() => {
-        initAppHabitDB(appHabitDB, setAppHabitDB, dbList, setDbList);
-    }, [appHabitDB])
     return (
         <div id="mainDiv" data-tauri-drag-region>
             <div style={{width: "100%", height: "51.5px", margin_bottom: "5px"}}>
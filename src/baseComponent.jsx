@@ -53,6 +53,11 @@ async function action_rebuildAppIndex() {
     return await invoke("create_app_index", {})
 }
 
+async function action_findDuplicateFiles() {
+    // 在后台查找，完成后在「重复文件」中查看
+    await invoke("find_duplicate_files", {});
+}
+
 export default {
     action_openApp: open_app,
     action_openUrl: open_url,
@@ -63,5 +68,6 @@ export default {
     action_readFile,
     action_rebuildFileIndex,
     action_rebuildAppIndex,
+    action_findDuplicateFiles,
     action_result,
 };
\ No newline at end of file
@@ -174,6 +174,8 @@ function TemplateComponent({components, selectedKey, setSelectedKey, confirmComp
                                         <div className="templateContent">
                                             <div className="templateTitle">
                                                 {component.title}
+                                                {component.badge &&
+                                                    <span className="templateBadge">{component.badge}</span>}
                                             </div>
                                             <div className="templateDesc">
                                                 {displayDesc(component, selectedKey === index)}
@@ -361,6 +363,19 @@ const FileIndexComponent = {
     type: "action",
     action: "rebuildFileIndex",
 };
+const duplicateFileComponent = {
+    icon: <img src={fileImg} alt="duplicate" className='activateComponent' data-tauri-drag-region/>,
+    title: '重复文件',
+    desc: 'duplicate files',
+    type: "component",
+};
+const FindDuplicateComponent = {
+    icon: <img src={rebuildImg} alt="duplicate" className='activateComponent' data-tauri-drag-region/>,
+    title: '查找重复文件',
+    desc: 'Find Duplicates',
+    type: "action",
+    action: "findDuplicateFiles",
+};
 const AppIndexComponent = {
     icon: <img src={rebuildImg} alt="index" className='activateComponent' data-tauri-drag-region/>,
     title: '重建应用索引',
@@ -384,7 +399,9 @@ const pluginsComponent = {
     settingPluginComponent,
     clipboardPluginComponent,
     FileIndexComponent,
-    AppIndexComponent
+    AppIndexComponent,
+    duplicateFileComponent,
+    FindDuplicateComponent
 }
 
 function calculateExpression(expression) {
@@ -414,15 +431,6 @@ const db_app_params = {
         {name: 'type', unique: false},
     ]
 };
-const db_app_habit_params = {
-    dbName: "lark",
-    cacheTableName: "appHabit",
-    keyPath: "keyword",
-    indexs: [
-        {name: 'keyword', unique: true},
-        {name: 'habitData', unique: false},
-    ]
-};
 
 const modifyWindowSize = async (size) => {
     if (size === "big") {
@@ -478,17 +486,6 @@ const initIndexDB = async (db, version, dbList, setDbList) => {
 };
 
 
-function initAppHabitDB(appHabitDB, setAppHabitDB, dbList, setDbList) {
-    if (appHabitDB && !appHabitDB._db) {
-        let version = dbList.includes(appHabitDB._cacheTableName) ? dbList.length : dbList.length + 1;
-        initIndexDB(appHabitDB, version, dbList, setDbList).then(() => {
-        });
-    } else if (!appHabitDB) {
-        setAppHabitDB(new IndexDBCache(db_app_habit_params))
-    }
-}
-
-
 export {
     TemplateComponent,
     SubpageComponent,
@@ -498,6 +495,5 @@ export {
     calculateExpression,
     modifyWindowSize,
     getWindowPosition,
-    loadCustomComponent,
-    initAppHabitDB
+    loadCustomComponent
 };
\ No newline at end of file